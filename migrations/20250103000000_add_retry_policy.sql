@@ -0,0 +1,13 @@
+-- Migration: Add retry policy and attempt counter
+-- Failed callbacks are retried with exponential backoff according to the
+-- timer's retry_policy. The attempts column counts every callback dispatch.
+
+-- Step 1: Add attempt counter (existing timers start at zero)
+ALTER TABLE timers
+ADD COLUMN attempts INTEGER NOT NULL DEFAULT 0;
+
+-- Step 2: Add optional retry policy (NULL = single attempt, no retries)
+ALTER TABLE timers
+ADD COLUMN retry_policy JSONB;
+
+-- Migration complete: failed attempts return to 'pending' until the policy is exhausted
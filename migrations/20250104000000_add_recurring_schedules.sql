@@ -0,0 +1,27 @@
+-- Migration: Add recurring schedules and execution history
+-- A timer with a schedule is rescheduled to its next occurrence after each run
+-- instead of reaching a terminal status.
+
+-- Step 1: Add optional schedule (NULL = one-shot timer) and occurrence counter
+ALTER TABLE timers
+ADD COLUMN schedule JSONB,
+ADD COLUMN occurrences INTEGER NOT NULL DEFAULT 0;
+
+-- Step 2: Create execution history (one row per callback dispatch)
+CREATE TABLE timer_executions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    timer_id UUID NOT NULL REFERENCES timers(id) ON DELETE CASCADE,
+    occurrence INTEGER NOT NULL,
+    attempt INTEGER NOT NULL,
+    scheduled_at TIMESTAMPTZ NOT NULL,
+    executed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    status VARCHAR(20) NOT NULL,
+    error TEXT,
+
+    CONSTRAINT valid_execution_status CHECK (status IN ('completed', 'failed'))
+);
+
+-- Step 3: Index for per-timer history lookups (newest first)
+CREATE INDEX idx_timer_executions_timer_id ON timer_executions(timer_id, executed_at DESC);
+
+-- Migration complete: recurring timers keep one row in timers and many in timer_executions
@@ -0,0 +1,23 @@
+-- Migration: Add dispatch details to execution history
+-- Every callback dispatch records its timing, the transport-level response
+-- and the scheduler instance that ran it.
+
+-- Step 1: executed_at becomes finished_at, paired with a new started_at
+ALTER TABLE timer_executions
+RENAME COLUMN executed_at TO finished_at;
+
+ALTER TABLE timer_executions
+ADD COLUMN started_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+ADD COLUMN duration_ms BIGINT NOT NULL DEFAULT 0;
+
+-- Step 2: Backfill timing for rows recorded before this migration
+UPDATE timer_executions SET started_at = finished_at;
+
+-- Step 3: Add response details and scheduler instance
+ALTER TABLE timer_executions
+ADD COLUMN http_status INTEGER,
+ADD COLUMN nats_ack BOOLEAN,
+ADD COLUMN response_body TEXT,
+ADD COLUMN instance_id VARCHAR(255);
+
+-- Migration complete: timer_executions exposed via GET /timers/:id/executions
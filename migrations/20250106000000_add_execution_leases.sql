@@ -0,0 +1,17 @@
+-- Migration: Add execution leases
+-- A timer in 'executing' holds a lease. If the scheduler dies before finishing
+-- the callback, the lease expires and the lease sweeper returns the timer to
+-- 'pending' (or 'failed' once too many attempts have been made).
+
+-- Step 1: Add lease expiry column
+ALTER TABLE timers
+ADD COLUMN lease_expires_at TIMESTAMPTZ;
+
+-- Step 2: Timers stuck in 'executing' before this migration are recovered on the first sweep
+UPDATE timers SET lease_expires_at = NOW() WHERE status = 'executing';
+
+-- Step 3: Index for the lease sweeper
+CREATE INDEX idx_timers_lease_expires_at ON timers(lease_expires_at)
+    WHERE status = 'executing';
+
+-- Migration complete: executing timers can no longer get stuck forever
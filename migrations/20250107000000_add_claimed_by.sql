@@ -0,0 +1,9 @@
+-- Migration: Add owning scheduler instance
+-- Due timers are claimed atomically (FOR UPDATE SKIP LOCKED) so that each
+-- timer fires once no matter how many scheduler replicas are running.
+
+-- Step 1: Add instance that claimed the timer for execution
+ALTER TABLE timers
+ADD COLUMN claimed_by VARCHAR(255);
+
+-- Migration complete: claimed_by records the instance that last claimed the timer
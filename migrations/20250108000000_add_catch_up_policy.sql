@@ -0,0 +1,25 @@
+-- Migration: Add catch-up policy for overdue timers
+-- The memory loader no longer ignores timers more than 5 minutes overdue.
+-- Each timer records how it is handled when found late:
+--   fire_all    = always fire, however late
+--   fire_within = fire if no later than max_lateness_secs, otherwise mark 'missed'
+--   mark_missed = never catch up; timers not fired on time are marked 'missed'
+
+-- Step 1: Add catch-up policy columns (existing timers keep firing when late)
+ALTER TABLE timers
+ADD COLUMN catch_up_policy VARCHAR(20) NOT NULL DEFAULT 'fire_all',
+ADD COLUMN max_lateness_secs INTEGER;
+
+ALTER TABLE timers
+ADD CONSTRAINT valid_catch_up_policy CHECK (catch_up_policy IN ('fire_all', 'fire_within', 'mark_missed'));
+
+-- Step 2: Allow the new 'missed' status on timers and executions
+ALTER TABLE timers
+DROP CONSTRAINT valid_status,
+ADD CONSTRAINT valid_status CHECK (status IN ('pending', 'executing', 'completed', 'failed', 'canceled', 'missed'));
+
+ALTER TABLE timer_executions
+DROP CONSTRAINT valid_execution_status,
+ADD CONSTRAINT valid_execution_status CHECK (status IN ('completed', 'failed', 'missed'));
+
+-- Migration complete: overdue timers are fired or marked missed, never left pending
@@ -0,0 +1,10 @@
+-- Migration: Add Kafka callback type
+-- Kafka callbacks store their topic, key, headers, payload and partition hint
+-- in callback_config like the other callback types.
+
+-- Step 1: Allow the new 'kafka' callback type
+ALTER TABLE timers
+DROP CONSTRAINT valid_callback_type,
+ADD CONSTRAINT valid_callback_type CHECK (callback_type IN ('http', 'nats', 'kafka'));
+
+-- Migration complete: timers can use http, nats or kafka callbacks
@@ -0,0 +1,10 @@
+-- Migration: Add Redis callback type
+-- Redis callbacks store their key, mode (stream or publish), extra stream
+-- fields, payload and trim length in callback_config like the other types.
+
+-- Step 1: Allow the new 'redis' callback type
+ALTER TABLE timers
+DROP CONSTRAINT valid_callback_type,
+ADD CONSTRAINT valid_callback_type CHECK (callback_type IN ('http', 'nats', 'kafka', 'redis'));
+
+-- Migration complete: timers can use http, nats, kafka or redis callbacks
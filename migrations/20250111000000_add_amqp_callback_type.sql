@@ -0,0 +1,10 @@
+-- Migration: Add AMQP callback type
+-- AMQP callbacks store their exchange, routing key, headers, payload and
+-- delivery options in callback_config like the other callback types.
+
+-- Step 1: Allow the new 'amqp' callback type
+ALTER TABLE timers
+DROP CONSTRAINT valid_callback_type,
+ADD CONSTRAINT valid_callback_type CHECK (callback_type IN ('http', 'nats', 'kafka', 'redis', 'amqp'));
+
+-- Migration complete: timers can use http, nats, kafka, redis or amqp callbacks
@@ -0,0 +1,10 @@
+-- Migration: Add gRPC callback type
+-- gRPC callbacks store their target, method, metadata, payload (protobuf JSON
+-- mapping), deadline and status code handling in callback_config.
+
+-- Step 1: Allow the new 'grpc' callback type
+ALTER TABLE timers
+DROP CONSTRAINT valid_callback_type,
+ADD CONSTRAINT valid_callback_type CHECK (callback_type IN ('http', 'nats', 'kafka', 'redis', 'amqp', 'grpc'));
+
+-- Migration complete: timers can use http, nats, kafka, redis, amqp or grpc callbacks
@@ -0,0 +1,11 @@
+-- Migration: Add Postgres callback type
+-- Postgres callbacks store their mode (outbox or notify), table or channel and
+-- payload in callback_config. Outbox tables are owned by their consumers and
+-- must be listed in POSTGRES_CALLBACK_TABLES.
+
+-- Step 1: Allow the new 'postgres' callback type
+ALTER TABLE timers
+DROP CONSTRAINT valid_callback_type,
+ADD CONSTRAINT valid_callback_type CHECK (callback_type IN ('http', 'nats', 'kafka', 'redis', 'amqp', 'grpc', 'postgres'));
+
+-- Migration complete: timers can use http, nats, kafka, redis, amqp, grpc or postgres callbacks
@@ -0,0 +1,16 @@
+-- Migration: Add idempotency keys for timer creation
+-- POST /timers accepts an Idempotency-Key header. A repeated create with the
+-- same key returns the timer created first instead of inserting a new one;
+-- idempotency_fingerprint (SHA-256 of the request) detects a key reused for a
+-- different request.
+
+-- Step 1: Add idempotency key and request fingerprint columns
+ALTER TABLE timers
+ADD COLUMN idempotency_key VARCHAR(255),
+ADD COLUMN idempotency_fingerprint VARCHAR(64);
+
+-- Step 2: Each key creates at most one timer
+CREATE UNIQUE INDEX idx_timers_idempotency_key ON timers(idempotency_key)
+WHERE idempotency_key IS NOT NULL;
+
+-- Migration complete: creates carrying an already used Idempotency-Key are deduplicated
@@ -0,0 +1,18 @@
+-- Migration: Add external keys
+-- Timers can be created with a caller-chosen (namespace, external_key) pair,
+-- e.g. ('orders', 'order-123-reminder'), and then read, updated and canceled
+-- through /timers/by-key/{namespace}/{key} instead of their UUID.
+
+-- Step 1: Add namespace and external key columns (both set or both NULL)
+ALTER TABLE timers
+ADD COLUMN namespace VARCHAR(255),
+ADD COLUMN external_key VARCHAR(255);
+
+ALTER TABLE timers
+ADD CONSTRAINT valid_external_key CHECK ((namespace IS NULL) = (external_key IS NULL));
+
+-- Step 2: Each key identifies at most one timer within its namespace
+CREATE UNIQUE INDEX idx_timers_namespace_external_key ON timers(namespace, external_key)
+WHERE external_key IS NOT NULL;
+
+-- Migration complete: timers can be addressed by (namespace, external_key)
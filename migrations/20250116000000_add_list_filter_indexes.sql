@@ -0,0 +1,15 @@
+-- Migration: Add indexes for list filters
+-- GET /timers filters by metadata containment, execute_at range and callback
+-- URL / topic prefix besides status, callback_type and created_at (already indexed).
+
+-- Step 1: Metadata containment (metadata @> '{...}', also used by the tag filter)
+CREATE INDEX idx_timers_metadata ON timers USING GIN (metadata jsonb_path_ops);
+
+-- Step 2: execute_at ranges over all statuses (idx_timers_execute_at_status only covers pending timers)
+CREATE INDEX idx_timers_execute_at ON timers(execute_at);
+
+-- Step 3: Callback URL and topic prefixes (LIKE 'prefix%')
+CREATE INDEX idx_timers_callback_url ON timers((callback_config->>'url') text_pattern_ops);
+CREATE INDEX idx_timers_callback_topic ON timers((callback_config->>'topic') text_pattern_ops);
+
+-- Migration complete: list filters are served by indexes
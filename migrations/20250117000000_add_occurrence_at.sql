@@ -0,0 +1,15 @@
+-- Migration: Add occurrence scheduled time
+-- Retries move execute_at to the backed-off attempt time, so the time the
+-- current occurrence was scheduled for is kept separately. Recurring timers
+-- compute their next occurrence from it.
+
+-- Step 1: Add occurrence_at, starting at the current execute_at
+ALTER TABLE timers
+ADD COLUMN occurrence_at TIMESTAMPTZ;
+
+UPDATE timers SET occurrence_at = execute_at;
+
+ALTER TABLE timers
+ALTER COLUMN occurrence_at SET NOT NULL;
+
+-- Migration complete: retries no longer shift recurring schedules
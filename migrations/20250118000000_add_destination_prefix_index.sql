@@ -0,0 +1,13 @@
+-- Migration: Index the topic_prefix list filter over all message destinations
+-- topic_prefix matches the NATS/Kafka topic, the Redis key or the AMQP routing
+-- key, so the topic-only index is replaced by one on the same expression.
+
+-- Step 1: Drop the topic-only index
+DROP INDEX IF EXISTS idx_timers_callback_topic;
+
+-- Step 2: Destination prefixes (LIKE 'prefix%')
+CREATE INDEX idx_timers_callback_destination ON timers((
+    COALESCE(callback_config->>'topic', callback_config->>'key', callback_config->>'routing_key')
+) text_pattern_ops);
+
+-- Migration complete: topic_prefix is served by an index for every message callback type
@@ -0,0 +1,181 @@
+use axum::{extract::State, http::StatusCode, Json};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use crate::{
+    api_create_timer::{build_new_timer, external_key_label, CreateTimerRequest},
+    db,
+    models::{ApiResponse, AppState, TimerResponse},
+};
+
+/// Maximum number of timers in one batch
+pub const MAX_BATCH_TIMERS: usize = 10_000;
+
+/// Request body limit of the batch endpoint (the default 2 MB is too small)
+pub const MAX_BATCH_BODY_BYTES: usize = 16 * 1024 * 1024;
+
+#[derive(Debug, Deserialize)]
+pub struct BatchCreateTimersRequest {
+    pub timers: Vec<CreateTimerRequest>,
+    /// Create nothing if any timer is rejected (default true)
+    #[serde(default = "default_all_or_nothing")]
+    pub all_or_nothing: bool,
+}
+
+fn default_all_or_nothing() -> bool {
+    true
+}
+
+/// Result for one timer of the batch (timer if created, error otherwise)
+#[derive(Debug, Serialize)]
+pub struct BatchItemResult {
+    /// Position of the timer in the request
+    pub index: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timer: Option<TimerResponse>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BatchCreateTimersResponse {
+    pub created: usize,
+    pub failed: usize,
+    pub results: Vec<BatchItemResult>,
+}
+
+impl BatchCreateTimersResponse {
+    fn new(results: Vec<BatchItemResult>) -> Self {
+        let failed = results.iter().filter(|r| r.error.is_some()).count();
+        Self {
+            created: results.iter().filter(|r| r.timer.is_some()).count(),
+            failed,
+            results,
+        }
+    }
+}
+
+pub async fn batch_create_timers(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<BatchCreateTimersRequest>,
+) -> Result<
+    (StatusCode, Json<ApiResponse<BatchCreateTimersResponse>>),
+    (StatusCode, Json<ApiResponse<()>>),
+> {
+    // Validate batch size
+    if req.timers.is_empty() || req.timers.len() > MAX_BATCH_TIMERS {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<()>::error(
+                2,
+                format!("timers must contain 1 to {} timers", MAX_BATCH_TIMERS),
+            )),
+        ));
+    }
+
+    let total = req.timers.len();
+    let mut results: Vec<BatchItemResult> = (0..total)
+        .map(|index| BatchItemResult {
+            index,
+            timer: None,
+            error: None,
+        })
+        .collect();
+
+    // Validate each timer independently
+    let mut labels = Vec::with_capacity(total);
+    let mut seen_keys = HashSet::new();
+    let mut valid = Vec::with_capacity(total);
+    for (index, timer_req) in req.timers.into_iter().enumerate() {
+        let label = external_key_label(&timer_req);
+        match build_new_timer(&state, timer_req) {
+            Ok(_)
+                if label
+                    .as_ref()
+                    .is_some_and(|label| !seen_keys.insert(label.clone())) =>
+            {
+                results[index].error = Some(format!(
+                    "external key '{}' is used more than once in the batch",
+                    label.as_deref().unwrap_or_default()
+                ));
+            }
+            Ok(new_timer) => valid.push((index, new_timer)),
+            Err(msg) => results[index].error = Some(msg),
+        }
+        labels.push(label);
+    }
+
+    if req.all_or_nothing && valid.len() < total {
+        let response = BatchCreateTimersResponse::new(results);
+        return Ok((
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse {
+                code: 2,
+                message: format!(
+                    "{} of {} timers are invalid; no timers were created",
+                    response.failed, total
+                ),
+                data: Some(response),
+            }),
+        ));
+    }
+
+    // Insert the valid timers in one transaction
+    let (indices, new_timers): (Vec<usize>, Vec<db::NewTimer>) = valid.into_iter().unzip();
+    let taken_error = |index: usize| {
+        format!(
+            "external key '{}' is already used by another timer",
+            labels[index].as_deref().unwrap_or_default()
+        )
+    };
+
+    match db::db_create_timers(&state.pool, new_timers, req.all_or_nothing).await {
+        Ok(db::CreatedTimers::Inserted(timers)) => {
+            for (index, timer) in indices.into_iter().zip(timers) {
+                match timer {
+                    Some(timer) => results[index].timer = Some(timer.to_response()),
+                    None => results[index].error = Some(taken_error(index)),
+                }
+            }
+
+            let response = BatchCreateTimersResponse::new(results);
+            // 201 when every timer was created, 200 with per-timer errors otherwise
+            let status = if response.failed == 0 {
+                StatusCode::CREATED
+            } else {
+                StatusCode::OK
+            };
+            Ok((status, Json(ApiResponse::success(response))))
+        }
+        Ok(db::CreatedTimers::RolledBack(taken)) => {
+            for position in taken {
+                let index = indices[position];
+                results[index].error = Some(taken_error(index));
+            }
+
+            let response = BatchCreateTimersResponse::new(results);
+            Ok((
+                StatusCode::CONFLICT,
+                Json(ApiResponse {
+                    code: 5,
+                    message: format!(
+                        "{} of {} timers use an external key that is already taken; no timers were created",
+                        response.failed, total
+                    ),
+                    data: Some(response),
+                }),
+            ))
+        }
+        Err(err) => {
+            tracing::error!("Failed to create timer batch: {}", err);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error(
+                    1,
+                    format!("Database error: {}", err),
+                )),
+            ))
+        }
+    }
+}
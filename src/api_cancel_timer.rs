@@ -43,10 +43,10 @@ pub async fn cancel_timer(
         }
     };
 
-    // Reject if status is completed or failed
+    // Reject if status is completed, failed, or missed
     if matches!(
         existing_timer.status,
-        TimerStatus::Completed | TimerStatus::Failed
+        TimerStatus::Completed | TimerStatus::Failed | TimerStatus::Missed
     ) {
         return Err((
             StatusCode::BAD_REQUEST,
@@ -0,0 +1,132 @@
+use axum::{extract::State, http::StatusCode, Json};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::{
+    db,
+    models::{ApiResponse, AppState},
+};
+
+#[derive(Debug, Deserialize)]
+pub struct CancelTimersRequest {
+    /// Metadata contains this object
+    pub metadata: Option<serde_json::Value>,
+    /// Metadata "tags" array contains this tag
+    pub tag: Option<String>,
+    pub namespace: Option<String>,
+    pub external_key_prefix: Option<String>,
+    /// Host of the HTTP callback URL or gRPC target
+    pub callback_host: Option<String>,
+    pub execute_at_from: Option<DateTime<Utc>>,
+    pub execute_at_to: Option<DateTime<Utc>>,
+    /// Only count the matching timers
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CancelTimersResponse {
+    /// Pending timers matching the filter
+    pub matched: u64,
+    /// Timers canceled (0 on a dry run)
+    pub canceled: u64,
+    pub dry_run: bool,
+}
+
+pub async fn cancel_timers(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<CancelTimersRequest>,
+) -> Result<
+    (StatusCode, Json<ApiResponse<CancelTimersResponse>>),
+    (StatusCode, Json<ApiResponse<()>>),
+> {
+    let filter = db::TimerFilter {
+        metadata: req.metadata,
+        tag: req.tag,
+        namespace: req.namespace,
+        external_key_prefix: req.external_key_prefix,
+        callback_host: req.callback_host,
+        execute_at_from: req.execute_at_from,
+        execute_at_to: req.execute_at_to,
+        ..Default::default()
+    };
+
+    if let Err(msg) = validate_filter(&filter) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<()>::error(2, msg)),
+        ));
+    }
+
+    match db::db_cancel_timers(&state.pool, &filter, req.dry_run).await {
+        Ok(count) => {
+            tracing::info!(
+                "Bulk cancel matched {} pending timers (dry_run: {})",
+                count,
+                req.dry_run
+            );
+            let response = CancelTimersResponse {
+                matched: count,
+                canceled: if req.dry_run { 0 } else { count },
+                dry_run: req.dry_run,
+            };
+            Ok((StatusCode::OK, Json(ApiResponse::success(response))))
+        }
+        Err(err) => {
+            tracing::error!("Failed to cancel timers: {}", err);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error(1, format!("Database error: {}", err))),
+            ))
+        }
+    }
+}
+
+/// Validate a bulk cancel filter (at least one condition is required)
+fn validate_filter(filter: &db::TimerFilter) -> Result<(), String> {
+    let db::TimerFilter {
+        metadata,
+        tag,
+        namespace,
+        external_key_prefix,
+        callback_host,
+        execute_at_from,
+        execute_at_to,
+        ..
+    } = filter;
+
+    if metadata.is_none()
+        && tag.is_none()
+        && namespace.is_none()
+        && external_key_prefix.is_none()
+        && callback_host.is_none()
+        && execute_at_from.is_none()
+        && execute_at_to.is_none()
+    {
+        return Err("at least one filter is required".to_string());
+    }
+
+    if metadata.as_ref().is_some_and(|m| !m.is_object()) {
+        return Err("metadata must be a JSON object".to_string());
+    }
+
+    for (name, value) in [
+        ("tag", tag),
+        ("namespace", namespace),
+        ("external_key_prefix", external_key_prefix),
+        ("callback_host", callback_host),
+    ] {
+        if value.as_ref().is_some_and(|v| v.trim().is_empty()) {
+            return Err(format!("{} must not be empty", name));
+        }
+    }
+
+    if let (Some(from), Some(to)) = (execute_at_from, execute_at_to) {
+        if from >= to {
+            return Err("execute_at_from must be before execute_at_to".to_string());
+        }
+    }
+
+    Ok(())
+}
@@ -1,101 +1,167 @@
-use axum::{extract::State, http::StatusCode, Json};
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    Json,
+};
 use chrono::{Duration, Utc};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::sync::Arc;
 
 use crate::{
     db,
-    models::{ApiResponse, AppState, CallbackConfig, CallbackType, TimerResponse},
+    models::{
+        ApiResponse, AppState, CallbackConfig, CallbackType, CatchUpPolicy, RetryPolicy, Schedule,
+        TimerResponse,
+    },
+    recurrence,
 };
 
-#[derive(Debug, Deserialize)]
+/// Header carrying the client-supplied key that deduplicates repeated creates
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// Maximum length of an idempotency key
+pub const MAX_IDEMPOTENCY_KEY_LEN: usize = 255;
+
+/// Maximum length of a namespace or external key
+pub const MAX_EXTERNAL_KEY_LEN: usize = 255;
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct CreateTimerRequest {
-    pub execute_at: chrono::DateTime<Utc>,
+    /// First (or only) run; optional when a schedule is provided
+    pub execute_at: Option<chrono::DateTime<Utc>>,
     pub callback: CallbackConfig,
     pub metadata: Option<serde_json::Value>,
+    pub retry_policy: Option<RetryPolicy>,
+    pub schedule: Option<Schedule>,
+    /// Handling when found overdue (defaults to CATCH_UP_POLICY)
+    pub catch_up_policy: Option<CatchUpPolicy>,
+    pub max_lateness_secs: Option<i32>,
+    /// Caller-chosen key for /timers/by-key/{namespace}/{key} (requires external_key)
+    pub namespace: Option<String>,
+    pub external_key: Option<String>,
 }
 
 pub async fn create_timer(
     State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
     Json(req): Json<CreateTimerRequest>,
 ) -> Result<
     (StatusCode, Json<ApiResponse<TimerResponse>>),
     (StatusCode, Json<ApiResponse<()>>),
 > {
-    // Validate execute_at is in future (> NOW + 5 seconds)
-    let now = Utc::now();
-    let min_execute_time = now + Duration::seconds(5);
-
-    if req.execute_at <= min_execute_time {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(ApiResponse::<()>::error(
-                2,
-                "execute_at must be at least 5 seconds in the future",
-            )),
-        ));
-    }
-
-    // Validate callback configuration
-    match &req.callback {
-        CallbackConfig::Http(http) => {
-            if !http.url.starts_with("http://") && !http.url.starts_with("https://") {
-                return Err((
-                    StatusCode::BAD_REQUEST,
-                    Json(ApiResponse::<()>::error(
-                        2,
-                        "HTTP callback URL must start with http:// or https://",
-                    )),
-                ));
+    // Validate idempotency key if provided
+    let idempotency_key = match headers.get(IDEMPOTENCY_KEY_HEADER) {
+        Some(value) => match value.to_str() {
+            Ok(key) if !key.trim().is_empty() && key.len() <= MAX_IDEMPOTENCY_KEY_LEN => {
+                Some(key.to_string())
             }
-        }
-        CallbackConfig::Nats(nats) => {
-            // Validate NATS is available if requested
-            if state.nats_client.is_none() {
+            _ => {
                 return Err((
                     StatusCode::BAD_REQUEST,
                     Json(ApiResponse::<()>::error(
                         2,
-                        "NATS callbacks not available (NATS_URL not configured)",
+                        format!(
+                            "Idempotency-Key must be 1 to {} visible ASCII characters",
+                            MAX_IDEMPOTENCY_KEY_LEN
+                        ),
                     )),
                 ));
             }
-            // Validate topic is not empty
-            if nats.topic.trim().is_empty() {
+        },
+        None => None,
+    };
+
+    // Fingerprint the request so a reused key with a different body is detected
+    let idempotency_fingerprint = match &idempotency_key {
+        Some(_) => match serde_json::to_vec(&req) {
+            Ok(body) => Some(hex::encode(Sha256::digest(body))),
+            Err(err) => {
                 return Err((
-                    StatusCode::BAD_REQUEST,
+                    StatusCode::INTERNAL_SERVER_ERROR,
                     Json(ApiResponse::<()>::error(
-                        2,
-                        "NATS topic cannot be empty",
+                        1,
+                        format!("Failed to fingerprint request: {}", err),
                     )),
                 ));
             }
+        },
+        None => None,
+    };
+
+    // Answer a repeated create before validating it: time-relative checks
+    // (execute_at at least 5 seconds ahead) no longer hold when a client retries later
+    let existing = match (&idempotency_key, &idempotency_fingerprint) {
+        (Some(key), Some(fingerprint)) => {
+            match db::db_find_idempotent_create(&state.pool, key, fingerprint).await {
+                Ok(existing) => existing,
+                Err(err) => {
+                    tracing::error!("Failed to look up idempotency key: {}", err);
+                    return Err((
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Json(ApiResponse::<()>::error(
+                            1,
+                            format!("Database error: {}", err),
+                        )),
+                    ));
+                }
+            }
         }
-    }
+        _ => None,
+    };
 
-    // Determine callback_type
-    let callback_type = match &req.callback {
-        CallbackConfig::Http(_) => CallbackType::Http,
-        CallbackConfig::Nats(_) => CallbackType::Nats,
+    let external_key_label = external_key_label(&req);
+    let created = match existing {
+        Some(created) => Ok(created),
+        None => {
+            // Validate the request and resolve defaults
+            let mut new_timer = match build_new_timer(&state, req) {
+                Ok(new_timer) => new_timer,
+                Err(msg) => {
+                    return Err((
+                        StatusCode::BAD_REQUEST,
+                        Json(ApiResponse::<()>::error(2, msg)),
+                    ));
+                }
+            };
+            new_timer.idempotency_key = idempotency_key;
+            new_timer.idempotency_fingerprint = idempotency_fingerprint;
+
+            // A concurrent create with the same key is still resolved by the insert
+            db::db_create_timer(&state.pool, new_timer).await
+        }
     };
 
-    // Create timer in database
-    match db::db_create_timer(
-        &state.pool,
-        req.execute_at,
-        callback_type,
-        req.callback,
-        req.metadata,
-    )
-    .await
-    {
-        Ok(timer) => {
+    match created {
+        Ok(db::CreatedTimer::New(timer)) => {
             let response = timer.to_response();
             Ok((
                 StatusCode::CREATED,
                 Json(ApiResponse::success(response)),
             ))
         }
+        // Repeated create: return the timer created the first time
+        Ok(db::CreatedTimer::Existing(timer)) => {
+            let response = timer.to_response();
+            Ok((StatusCode::OK, Json(ApiResponse::success(response))))
+        }
+        Ok(db::CreatedTimer::KeyConflict) => Err((
+            StatusCode::CONFLICT,
+            Json(ApiResponse::<()>::error(
+                5,
+                "Idempotency-Key was already used for a different request",
+            )),
+        )),
+        Ok(db::CreatedTimer::ExternalKeyTaken) => Err((
+            StatusCode::CONFLICT,
+            Json(ApiResponse::<()>::error(
+                5,
+                format!(
+                    "external key '{}' is already used by another timer",
+                    external_key_label.unwrap_or_default()
+                ),
+            )),
+        )),
         Err(err) => {
             tracing::error!("Failed to create timer: {}", err);
             Err((
@@ -105,3 +171,162 @@ pub async fn create_timer(
         }
     }
 }
+
+/// "namespace/external_key" of a request, for error messages
+pub fn external_key_label(req: &CreateTimerRequest) -> Option<String> {
+    let namespace = req.namespace.as_deref().unwrap_or_default();
+    req.external_key
+        .as_ref()
+        .map(|key| format!("{}/{}", namespace, key))
+}
+
+/// Validate a create request and resolve its defaults into a new timer
+///
+/// Shared by single and batch creates; the error is the validation message.
+pub fn build_new_timer(state: &AppState, req: CreateTimerRequest) -> Result<db::NewTimer, String> {
+    // Validate external key if provided
+    match (&req.namespace, &req.external_key) {
+        (Some(namespace), Some(external_key)) => {
+            for (name, value) in [("namespace", namespace), ("external_key", external_key)] {
+                if value.trim().is_empty() || value.len() > MAX_EXTERNAL_KEY_LEN {
+                    return Err(format!(
+                        "{} must be 1 to {} characters",
+                        name, MAX_EXTERNAL_KEY_LEN
+                    ));
+                }
+            }
+        }
+        (None, None) => {}
+        _ => return Err("namespace and external_key must be provided together".to_string()),
+    }
+
+    // Validate schedule if provided
+    if let Some(schedule) = &req.schedule {
+        schedule.validate()?;
+    }
+
+    // Validate execute_at is in future (> NOW + 5 seconds)
+    let now = Utc::now();
+    let min_execute_time = now + Duration::seconds(5);
+
+    let execute_at = match (req.execute_at, &req.schedule) {
+        (Some(execute_at), _) => execute_at,
+        // Recurring timers without execute_at start at their first occurrence
+        (None, Some(schedule)) => recurrence::first_occurrence(schedule, min_execute_time)
+            .ok_or("schedule has no occurrence before its until time")?,
+        (None, None) => {
+            return Err("execute_at is required unless a schedule is provided".to_string());
+        }
+    };
+
+    if execute_at <= min_execute_time {
+        return Err("execute_at must be at least 5 seconds in the future".to_string());
+    }
+
+    // Validate callback configuration
+    validate_callback(state, &req.callback)?;
+
+    // Validate retry policy if provided
+    if let Some(policy) = &req.retry_policy {
+        policy.validate()?;
+    }
+
+    // Resolve catch-up policy (stored on the timer so the choice is visible)
+    if matches!(req.max_lateness_secs, Some(secs) if secs < 1) {
+        return Err("max_lateness_secs must be at least 1".to_string());
+    }
+    let catch_up_policy = req
+        .catch_up_policy
+        .unwrap_or(state.config.default_catch_up_policy);
+    let max_lateness_secs = match catch_up_policy {
+        CatchUpPolicy::FireWithin => Some(
+            req.max_lateness_secs
+                .unwrap_or(state.config.default_max_lateness_secs),
+        ),
+        _ => req.max_lateness_secs,
+    };
+
+    Ok(db::NewTimer {
+        execute_at,
+        callback_type: callback_type(&req.callback),
+        callback_config: req.callback,
+        metadata: req.metadata,
+        retry_policy: req.retry_policy,
+        schedule: req.schedule,
+        catch_up_policy,
+        max_lateness_secs,
+        idempotency_key: None,
+        idempotency_fingerprint: None,
+        namespace: req.namespace,
+        external_key: req.external_key,
+    })
+}
+
+/// Validate callback configuration, including that its transport is configured
+///
+/// Shared by creates and updates.
+pub(crate) fn validate_callback(state: &AppState, callback: &CallbackConfig) -> Result<(), String> {
+    match callback {
+        CallbackConfig::Http(http) => http.validate(),
+        CallbackConfig::Nats(nats) => {
+            // Validate NATS is available if requested
+            if state.callback_clients.nats.is_none() {
+                return Err("NATS callbacks not available (NATS_URL not configured)".to_string());
+            }
+            nats.validate()
+        }
+        CallbackConfig::Kafka(kafka) => {
+            // Validate Kafka is available if requested
+            if state.callback_clients.kafka.is_none() {
+                return Err(
+                    "Kafka callbacks not available (KAFKA_BROKERS not configured)".to_string(),
+                );
+            }
+            kafka.validate()
+        }
+        CallbackConfig::Redis(redis) => {
+            // Validate Redis is available if requested
+            if state.callback_clients.redis.is_none() {
+                return Err("Redis callbacks not available (REDIS_HOST not configured)".to_string());
+            }
+            redis.validate()
+        }
+        CallbackConfig::Amqp(amqp) => {
+            // Validate AMQP is available if requested
+            if state.callback_clients.amqp.is_none() {
+                return Err("AMQP callbacks not available (AMQP_HOST not configured)".to_string());
+            }
+            amqp.validate()
+        }
+        CallbackConfig::Grpc(grpc) => {
+            // Validate gRPC is available if requested
+            let Some(client) = &state.callback_clients.grpc else {
+                return Err(
+                    "gRPC callbacks not available (GRPC_DESCRIPTOR_SETS not configured)"
+                        .to_string(),
+                );
+            };
+            grpc.validate().and_then(|_| client.check(grpc))
+        }
+        CallbackConfig::Postgres(pg) => {
+            // Validate Postgres callbacks are enabled if requested
+            let Some(targets) = &state.callback_clients.postgres else {
+                return Err("Postgres callbacks not available (POSTGRES_CALLBACK_TABLES / POSTGRES_CALLBACK_CHANNELS not configured)".to_string());
+            };
+            pg.validate().and_then(|_| targets.check(pg))
+        }
+    }
+}
+
+/// Callback type stored alongside a callback configuration
+pub(crate) fn callback_type(callback: &CallbackConfig) -> CallbackType {
+    match callback {
+        CallbackConfig::Http(_) => CallbackType::Http,
+        CallbackConfig::Nats(_) => CallbackType::Nats,
+        CallbackConfig::Kafka(_) => CallbackType::Kafka,
+        CallbackConfig::Redis(_) => CallbackType::Redis,
+        CallbackConfig::Amqp(_) => CallbackType::Amqp,
+        CallbackConfig::Grpc(_) => CallbackType::Grpc,
+        CallbackConfig::Postgres(_) => CallbackType::Postgres,
+    }
+}
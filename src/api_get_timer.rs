@@ -10,7 +10,7 @@ use uuid::Uuid;
 
 use crate::{
     db,
-    models::{ApiResponse, AppState, CallbackConfig},
+    models::{ApiResponse, AppState, CallbackConfig, CatchUpPolicy, RetryPolicy, Schedule},
 };
 
 #[derive(Debug, Serialize)]
@@ -24,6 +24,18 @@ pub struct TimerDetailResponse {
     pub last_error: Option<String>,
     pub executed_at: Option<DateTime<Utc>>,
     pub metadata: Option<serde_json::Value>,
+    pub attempts: i32,
+    pub retry_policy: Option<RetryPolicy>,
+    pub schedule: Option<Schedule>,
+    pub occurrences: i32,
+    /// Scheduled time of the current occurrence (execute_at may be a later retry)
+    pub occurrence_at: DateTime<Utc>,
+    pub lease_expires_at: Option<DateTime<Utc>>,
+    pub claimed_by: Option<String>,
+    pub catch_up_policy: CatchUpPolicy,
+    pub max_lateness_secs: Option<i32>,
+    pub namespace: Option<String>,
+    pub external_key: Option<String>,
 }
 
 pub async fn get_timer(
@@ -40,11 +52,22 @@ pub async fn get_timer(
                 created_at: timer.created_at,
                 updated_at: timer.updated_at,
                 execute_at: timer.execute_at,
-                callback: timer.callback_config,
+                callback: timer.callback_config.redacted(),
                 status: timer.status.to_string(),
                 last_error: timer.last_error,
                 executed_at: timer.executed_at,
                 metadata: timer.metadata,
+                attempts: timer.attempts,
+                retry_policy: timer.retry_policy.map(|p| p.0),
+                schedule: timer.schedule.map(|s| s.0),
+                occurrences: timer.occurrences,
+                occurrence_at: timer.occurrence_at,
+                lease_expires_at: timer.lease_expires_at,
+                claimed_by: timer.claimed_by,
+                catch_up_policy: timer.catch_up_policy,
+                max_lateness_secs: timer.max_lateness_secs,
+                namespace: timer.namespace,
+                external_key: timer.external_key,
             };
             Ok((StatusCode::OK, Json(ApiResponse::success(response))))
         }
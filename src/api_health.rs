@@ -3,12 +3,17 @@ use chrono::Utc;
 use serde::Serialize;
 use std::sync::Arc;
 
-use crate::models::{ApiResponse, AppState};
+use crate::dispatch_limiter::DispatchStats;
+use crate::models::{ApiResponse, AppState, SchedulerRole};
 
 #[derive(Debug, Serialize)]
 pub struct HealthData {
     pub status: String,
     pub database: String,
+    pub instance_id: String,
+    pub scheduler_mode: String,
+    pub scheduler_role: SchedulerRole,
+    pub dispatch: DispatchStats,
     pub timestamp: chrono::DateTime<Utc>,
 }
 
@@ -16,6 +21,8 @@ pub async fn health_check(
     State(state): State<Arc<AppState>>,
 ) -> (StatusCode, Json<ApiResponse<HealthData>>) {
     let timestamp = Utc::now();
+    let scheduler_role = *state.scheduler_role.read().await;
+    let dispatch = state.dispatch_limiter.stats();
 
     // Test database connection
     match sqlx::query("SELECT 1").fetch_one(&state.pool).await {
@@ -23,6 +30,10 @@ pub async fn health_check(
             let data = HealthData {
                 status: "up".to_string(),
                 database: "connected".to_string(),
+                instance_id: state.config.instance_id.clone(),
+                scheduler_mode: state.config.scheduler_mode.to_string(),
+                scheduler_role,
+                dispatch,
                 timestamp,
             };
             (StatusCode::OK, Json(ApiResponse::success(data)))
@@ -32,6 +43,10 @@ pub async fn health_check(
             let data = HealthData {
                 status: "degraded".to_string(),
                 database: "disconnected".to_string(),
+                instance_id: state.config.instance_id.clone(),
+                scheduler_mode: state.config.scheduler_mode.to_string(),
+                scheduler_role,
+                dispatch,
                 timestamp,
             };
             (
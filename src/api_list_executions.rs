@@ -0,0 +1,78 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    db,
+    models::{ApiResponse, AppState, TimerExecution},
+};
+
+#[derive(Debug, Deserialize)]
+pub struct ListExecutionsQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListExecutionsResponse {
+    pub executions: Vec<TimerExecution>,
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+pub async fn list_executions(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<ListExecutionsQuery>,
+) -> Result<
+    (StatusCode, Json<ApiResponse<ListExecutionsResponse>>),
+    (StatusCode, Json<ApiResponse<()>>),
+> {
+    // Set defaults
+    let limit = params.limit.unwrap_or(50).clamp(1, 200);
+    let offset = params.offset.unwrap_or(0).max(0);
+
+    // Ensure timer exists
+    match db::db_get_timer(&state.pool, id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return Err((
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::<()>::error(3, "timer not found")),
+            ));
+        }
+        Err(err) => {
+            tracing::error!("Failed to get timer {}: {}", id, err);
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error(1, format!("Database error: {}", err))),
+            ));
+        }
+    }
+
+    match db::db_list_executions(&state.pool, id, limit, offset).await {
+        Ok((executions, total)) => {
+            let response = ListExecutionsResponse {
+                executions,
+                total,
+                limit,
+                offset,
+            };
+
+            Ok((StatusCode::OK, Json(ApiResponse::success(response))))
+        }
+        Err(err) => {
+            tracing::error!("Failed to list executions for timer {}: {}", id, err);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error(1, format!("Database error: {}", err))),
+            ))
+        }
+    }
+}
@@ -3,6 +3,7 @@ use axum::{
     http::StatusCode,
     Json,
 };
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
@@ -11,9 +12,33 @@ use crate::{
     models::{ApiResponse, AppState, TimerResponse},
 };
 
+/// Values accepted by the status filter
+const STATUSES: &[&str] = &[
+    "pending",
+    "executing",
+    "completed",
+    "failed",
+    "canceled",
+    "missed",
+];
+
+/// Values accepted by the callback_type filter
+const CALLBACK_TYPES: &[&str] = &["http", "nats", "kafka", "redis", "amqp", "grpc", "postgres"];
+
 #[derive(Debug, Deserialize)]
 pub struct ListTimersQuery {
+    /// Comma-separated statuses (any of them)
     pub status: Option<String>,
+    /// Comma-separated callback types (any of them)
+    pub callback_type: Option<String>,
+    pub execute_at_from: Option<DateTime<Utc>>,
+    pub execute_at_to: Option<DateTime<Utc>>,
+    pub created_at_from: Option<DateTime<Utc>>,
+    pub created_at_to: Option<DateTime<Utc>>,
+    /// JSON object the metadata must contain
+    pub metadata: Option<String>,
+    pub url_prefix: Option<String>,
+    pub topic_prefix: Option<String>,
     pub limit: Option<i64>,
     pub offset: Option<i64>,
     pub sort: Option<String>,
@@ -36,7 +61,7 @@ pub async fn list_timers(
     (StatusCode, Json<ApiResponse<()>>),
 > {
     // Set defaults
-    let limit = params.limit.unwrap_or(50).min(200).max(1);
+    let limit = params.limit.unwrap_or(50).clamp(1, 200);
     let offset = params.offset.unwrap_or(0).max(0);
     let sort_field = params.sort.as_deref().unwrap_or("created_at");
     let sort_order = params.order.as_deref().unwrap_or("desc");
@@ -60,32 +85,17 @@ pub async fn list_timers(
         ));
     }
 
-    // Validate status filter if provided
-    if let Some(status) = &params.status {
-        if !matches!(
-            status.as_str(),
-            "pending" | "executing" | "completed" | "failed" | "canceled"
-        ) {
+    let filter = match build_filter(&params) {
+        Ok(filter) => filter,
+        Err(msg) => {
             return Err((
                 StatusCode::BAD_REQUEST,
-                Json(ApiResponse::<()>::error(
-                    2,
-                    "status must be one of: pending, executing, completed, failed, canceled",
-                )),
+                Json(ApiResponse::<()>::error(2, msg)),
             ));
         }
-    }
+    };
 
-    match db::db_list_timers(
-        &state.pool,
-        params.status.clone(),
-        limit,
-        offset,
-        sort_field,
-        sort_order,
-    )
-    .await
-    {
+    match db::db_list_timers(&state.pool, &filter, limit, offset, sort_field, sort_order).await {
         Ok((timers, total)) => {
             let timer_responses: Vec<TimerResponse> =
                 timers.iter().map(|t| t.to_response()).collect();
@@ -108,3 +118,64 @@ pub async fn list_timers(
         }
     }
 }
+
+/// Validate the filter parameters of a list request
+fn build_filter(params: &ListTimersQuery) -> Result<db::TimerFilter, String> {
+    let statuses = parse_list(params.status.as_deref(), "status", STATUSES)?;
+    let callback_types = parse_list(
+        params.callback_type.as_deref(),
+        "callback_type",
+        CALLBACK_TYPES,
+    )?;
+
+    let metadata = match &params.metadata {
+        Some(metadata) => match serde_json::from_str::<serde_json::Value>(metadata) {
+            Ok(value) if value.is_object() => Some(value),
+            _ => return Err("metadata must be a JSON object".to_string()),
+        },
+        None => None,
+    };
+
+    for (name, from, to) in [
+        ("execute_at", params.execute_at_from, params.execute_at_to),
+        ("created_at", params.created_at_from, params.created_at_to),
+    ] {
+        if let (Some(from), Some(to)) = (from, to) {
+            if from >= to {
+                return Err(format!("{}_from must be before {}_to", name, name));
+            }
+        }
+    }
+
+    Ok(db::TimerFilter {
+        statuses,
+        callback_types,
+        metadata,
+        execute_at_from: params.execute_at_from,
+        execute_at_to: params.execute_at_to,
+        created_at_from: params.created_at_from,
+        created_at_to: params.created_at_to,
+        url_prefix: params.url_prefix.clone(),
+        topic_prefix: params.topic_prefix.clone(),
+        ..Default::default()
+    })
+}
+
+/// Split a comma-separated filter value, checking each entry against the allowed values
+fn parse_list(value: Option<&str>, name: &str, allowed: &[&str]) -> Result<Vec<String>, String> {
+    let Some(value) = value else {
+        return Ok(Vec::new());
+    };
+
+    value
+        .split(',')
+        .map(|entry| {
+            let entry = entry.trim();
+            if allowed.contains(&entry) {
+                Ok(entry.to_string())
+            } else {
+                Err(format!("{} must be one of: {}", name, allowed.join(", ")))
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,72 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    api_cancel_timer::{self, CancelTimerResponse},
+    api_get_timer::{self, TimerDetailResponse},
+    api_update_timer::{self, UpdateTimerRequest},
+    db,
+    models::{ApiResponse, AppState, TimerResponse},
+};
+
+type ErrorResponse = (StatusCode, Json<ApiResponse<()>>);
+
+/// Resolve a (namespace, external_key) pair to the timer's id
+async fn resolve_key(
+    state: &AppState,
+    namespace: &str,
+    external_key: &str,
+) -> Result<Uuid, ErrorResponse> {
+    match db::db_find_timer_id_by_key(&state.pool, namespace, external_key).await {
+        Ok(Some(id)) => Ok(id),
+        Ok(None) => Err((
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::<()>::error(3, "timer not found")),
+        )),
+        Err(err) => {
+            tracing::error!(
+                "Failed to find timer by key {}/{}: {}",
+                namespace,
+                external_key,
+                err
+            );
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error(1, format!("Database error: {}", err))),
+            ))
+        }
+    }
+}
+
+/// GET /timers/by-key/:namespace/:key (same as GET /timers/:id)
+pub async fn get_timer_by_key(
+    State(state): State<Arc<AppState>>,
+    Path((namespace, external_key)): Path<(String, String)>,
+) -> Result<(StatusCode, Json<ApiResponse<TimerDetailResponse>>), ErrorResponse> {
+    let id = resolve_key(&state, &namespace, &external_key).await?;
+    api_get_timer::get_timer(State(state), Path(id)).await
+}
+
+/// PUT /timers/by-key/:namespace/:key (same as PUT /timers/:id)
+pub async fn update_timer_by_key(
+    State(state): State<Arc<AppState>>,
+    Path((namespace, external_key)): Path<(String, String)>,
+    Json(req): Json<UpdateTimerRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<TimerResponse>>), ErrorResponse> {
+    let id = resolve_key(&state, &namespace, &external_key).await?;
+    api_update_timer::update_timer(State(state), Path(id), Json(req)).await
+}
+
+/// DELETE /timers/by-key/:namespace/:key (same as DELETE /timers/:id)
+pub async fn cancel_timer_by_key(
+    State(state): State<Arc<AppState>>,
+    Path((namespace, external_key)): Path<(String, String)>,
+) -> Result<(StatusCode, Json<ApiResponse<CancelTimerResponse>>), ErrorResponse> {
+    let id = resolve_key(&state, &namespace, &external_key).await?;
+    api_cancel_timer::cancel_timer(State(state), Path(id)).await
+}
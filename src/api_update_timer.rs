@@ -9,8 +9,12 @@ use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::{
+    api_create_timer::{callback_type, validate_callback},
     db,
-    models::{ApiResponse, AppState, CallbackConfig, CallbackType, TimerResponse, TimerStatus},
+    models::{
+        ApiResponse, AppState, CallbackConfig, CatchUpPolicy, RetryPolicy, Schedule, TimerResponse,
+        TimerStatus,
+    },
 };
 
 #[derive(Debug, Deserialize)]
@@ -18,6 +22,10 @@ pub struct UpdateTimerRequest {
     pub execute_at: Option<chrono::DateTime<Utc>>,
     pub callback: Option<CallbackConfig>,
     pub metadata: Option<serde_json::Value>,
+    pub retry_policy: Option<RetryPolicy>,
+    pub schedule: Option<Schedule>,
+    pub catch_up_policy: Option<CatchUpPolicy>,
+    pub max_lateness_secs: Option<i32>,
 }
 
 pub async fn update_timer(
@@ -46,10 +54,10 @@ pub async fn update_timer(
         }
     };
 
-    // Reject if status is completed, failed, or canceled
+    // Reject if status is completed, failed, canceled, or missed
     if matches!(
         existing_timer.status,
-        TimerStatus::Completed | TimerStatus::Failed | TimerStatus::Canceled
+        TimerStatus::Completed | TimerStatus::Failed | TimerStatus::Canceled | TimerStatus::Missed
     ) {
         return Err((
             StatusCode::BAD_REQUEST,
@@ -77,59 +85,68 @@ pub async fn update_timer(
     }
 
     // Validate callback configuration if provided
-    let callback_type = if let Some(ref callback) = req.callback {
-        match callback {
-            CallbackConfig::Http(http) => {
-                if !http.url.starts_with("http://") && !http.url.starts_with("https://") {
-                    return Err((
-                        StatusCode::BAD_REQUEST,
-                        Json(ApiResponse::<()>::error(
-                            2,
-                            "HTTP callback URL must start with http:// or https://",
-                        )),
-                    ));
-                }
-                Some(CallbackType::Http)
-            }
-            CallbackConfig::Nats(nats) => {
-                // Validate NATS is available
-                if state.nats_client.is_none() {
-                    return Err((
-                        StatusCode::BAD_REQUEST,
-                        Json(ApiResponse::<()>::error(
-                            2,
-                            "NATS callbacks not available (NATS_URL not configured)",
-                        )),
-                    ));
-                }
-                // Validate topic is not empty
-                if nats.topic.trim().is_empty() {
-                    return Err((
-                        StatusCode::BAD_REQUEST,
-                        Json(ApiResponse::<()>::error(
-                            2,
-                            "NATS topic cannot be empty",
-                        )),
-                    ));
-                }
-                Some(CallbackType::Nats)
+    let callback_type = match &req.callback {
+        Some(callback) => {
+            if let Err(msg) = validate_callback(&state, callback) {
+                return Err((
+                    StatusCode::BAD_REQUEST,
+                    Json(ApiResponse::<()>::error(2, msg)),
+                ));
             }
+            Some(callback_type(callback))
         }
-    } else {
-        None
+        None => None,
+    };
+
+    // Validate retry policy if provided
+    if let Some(policy) = &req.retry_policy {
+        if let Err(msg) = policy.validate() {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::<()>::error(2, msg)),
+            ));
+        }
+    }
+
+    // Validate schedule if provided
+    if let Some(schedule) = &req.schedule {
+        if let Err(msg) = schedule.validate() {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::<()>::error(2, msg)),
+            ));
+        }
+    }
+
+    // Validate max lateness if provided
+    if matches!(req.max_lateness_secs, Some(secs) if secs < 1) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<()>::error(2, "max_lateness_secs must be at least 1")),
+        ));
+    }
+
+    // Switching to fire_within needs an allowed lateness
+    let max_lateness_secs = match (req.catch_up_policy, req.max_lateness_secs) {
+        (Some(CatchUpPolicy::FireWithin), None) if existing_timer.max_lateness_secs.is_none() => {
+            Some(state.config.default_max_lateness_secs)
+        }
+        (_, lateness) => lateness,
     };
 
     // Update timer
-    match db::db_update_timer(
-        &state.pool,
-        id,
-        req.execute_at,
+    let update = db::TimerUpdate {
+        execute_at: req.execute_at,
         callback_type,
-        req.callback,
-        req.metadata,
-    )
-    .await
-    {
+        callback_config: req.callback,
+        metadata: req.metadata,
+        retry_policy: req.retry_policy,
+        schedule: req.schedule,
+        catch_up_policy: req.catch_up_policy,
+        max_lateness_secs,
+    };
+
+    match db::db_update_timer(&state.pool, id, update).await {
         Ok(timer) => {
             let response = timer.to_response();
             Ok((StatusCode::OK, Json(ApiResponse::success(response))))
@@ -1,46 +1,379 @@
 //! Callback execution dispatcher module
-//! Routes callback execution to either HTTP or NATS based on callback_type
+//! Routes callback execution to HTTP, NATS, Kafka, Redis, AMQP, gRPC or Postgres based on callback_type
 
+use crate::callback_amqp::{execute_amqp_callback, AmqpPublisher};
+use crate::callback_grpc::{execute_grpc_callback, GrpcClient};
 use crate::callback_http::execute_http_callback;
+use crate::callback_kafka::execute_kafka_callback;
 use crate::callback_nats::execute_nats_callback;
-use crate::db::{db_mark_completed, db_mark_failed};
-use crate::models::{CallbackConfig, Timer};
+use crate::callback_postgres::{execute_postgres_callback, PostgresTargets};
+use crate::callback_redis::execute_redis_callback;
+use crate::db::{
+    db_mark_completed, db_mark_failed, db_mark_missed, db_record_execution,
+    db_schedule_next_occurrence, db_schedule_retry,
+};
+use crate::models::{CallbackConfig, ExecutionStatus, Timer, TimerExecution};
+use crate::recurrence::next_occurrence;
+use crate::retry::next_retry_at;
 use async_nats::Client as NatsClient;
+use chrono::{DateTime, Utc};
+use rdkafka::producer::FutureProducer;
+use redis::aio::ConnectionManager;
 use sqlx::PgPool;
+use std::sync::Arc;
 use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Maximum number of response body bytes kept in the execution history
+pub const MAX_RESPONSE_BODY_BYTES: usize = 4096;
+
+/// Keep at most MAX_RESPONSE_BODY_BYTES of a response body for the execution history
+pub fn truncate_body(body: &[u8]) -> Option<String> {
+    if body.is_empty() {
+        return None;
+    }
+    let body = &body[..body.len().min(MAX_RESPONSE_BODY_BYTES)];
+    Some(String::from_utf8_lossy(body).into_owned())
+}
+
+/// Transport-level details captured from a single callback dispatch
+#[derive(Debug, Clone, Default)]
+pub struct CallbackReport {
+    pub http_status: Option<i32>,
+    pub nats_ack: Option<bool>,
+    pub response_body: Option<String>,
+}
+
+/// Failed callback dispatch (error message plus whatever was captured before failing)
+#[derive(Debug, Clone)]
+pub struct CallbackError {
+    pub message: String,
+    pub report: CallbackReport,
+    /// Retrying cannot succeed; the retry policy is skipped
+    pub permanent: bool,
+}
+
+impl CallbackError {
+    pub fn with_report(message: impl Into<String>, report: CallbackReport) -> Self {
+        Self {
+            message: message.into(),
+            report,
+            permanent: false,
+        }
+    }
+
+    /// Failure that must not be retried
+    pub fn permanent(message: impl Into<String>, report: CallbackReport) -> Self {
+        Self {
+            permanent: true,
+            ..Self::with_report(message, report)
+        }
+    }
+}
+
+impl From<String> for CallbackError {
+    fn from(message: String) -> Self {
+        Self::with_report(message, CallbackReport::default())
+    }
+}
+
+/// Result returned by every callback handler
+pub type CallbackResult = Result<CallbackReport, CallbackError>;
+
+/// Long-lived clients shared by all callback dispatches
+#[derive(Clone)]
+pub struct CallbackClients {
+    /// Pooled HTTP client (connections and TLS sessions are reused across callbacks)
+    pub http: reqwest::Client,
+    /// Optional NATS client (None if NATS is not configured)
+    pub nats: Option<NatsClient>,
+    /// Optional Kafka producer (None if Kafka is not configured)
+    pub kafka: Option<FutureProducer>,
+    /// Optional Redis connection (None if Redis is not configured)
+    pub redis: Option<ConnectionManager>,
+    /// Optional AMQP publisher (None if AMQP is not configured)
+    pub amqp: Option<Arc<AmqpPublisher>>,
+    /// Optional gRPC client (None if no descriptor sets are registered)
+    pub grpc: Option<Arc<GrpcClient>>,
+    /// Optional Postgres callback targets (None if no tables or channels are allowed)
+    pub postgres: Option<PostgresTargets>,
+}
 
 /// Execute callback for a timer (dispatcher)
 ///
 /// Routes to the appropriate callback handler based on callback_config.
-/// Updates timer status in database based on execution result.
-pub async fn execute_callback(pool: &PgPool, timer: Timer, nats_client: Option<&NatsClient>) {
+/// Updates timer status in database based on execution result. Failed attempts
+/// are rescheduled according to the timer's retry policy and only marked failed
+/// once the policy is exhausted (or the failure is permanent). Recurring timers move on to their next occurrence
+/// instead of reaching a terminal status. Postgres callbacks update the status of a
+/// successful delivery themselves, in the transaction that delivers it.
+pub async fn execute_callback(
+    pool: &PgPool,
+    timer: Timer,
+    clients: &CallbackClients,
+    signing_secrets: &[String],
+    instance_id: &str,
+) {
     info!("Executing callback for timer {}", timer.id);
 
+    let started_at = Utc::now();
+
     // Dispatch to appropriate callback handler
     let result = match &timer.callback_config {
-        CallbackConfig::Http(http_config) => execute_http_callback(&timer, http_config).await,
+        CallbackConfig::Http(http_config) => {
+            execute_http_callback(&timer, http_config, &clients.http, signing_secrets).await
+        }
         CallbackConfig::Nats(nats_config) => {
-            if let Some(client) = nats_client {
+            if let Some(client) = &clients.nats {
                 execute_nats_callback(&timer, nats_config, client).await
             } else {
-                Err("NATS client not available (NATS_URL not configured)".to_string())
+                Err("NATS client not available (NATS_URL not configured)"
+                    .to_string()
+                    .into())
+            }
+        }
+        CallbackConfig::Kafka(kafka_config) => {
+            if let Some(producer) = &clients.kafka {
+                execute_kafka_callback(&timer, kafka_config, producer).await
+            } else {
+                Err("Kafka producer not available (KAFKA_BROKERS not configured)"
+                    .to_string()
+                    .into())
+            }
+        }
+        CallbackConfig::Redis(redis_config) => {
+            if let Some(connection) = &clients.redis {
+                execute_redis_callback(&timer, redis_config, connection.clone()).await
+            } else {
+                Err("Redis connection not available (REDIS_HOST not configured)"
+                    .to_string()
+                    .into())
+            }
+        }
+        CallbackConfig::Amqp(amqp_config) => {
+            if let Some(publisher) = &clients.amqp {
+                execute_amqp_callback(&timer, amqp_config, publisher).await
+            } else {
+                Err("AMQP publisher not available (AMQP_HOST not configured)"
+                    .to_string()
+                    .into())
+            }
+        }
+        CallbackConfig::Grpc(grpc_config) => {
+            if let Some(client) = &clients.grpc {
+                execute_grpc_callback(&timer, grpc_config, client).await
+            } else {
+                Err(
+                    "gRPC client not available (GRPC_DESCRIPTOR_SETS not configured)"
+                        .to_string()
+                        .into(),
+                )
+            }
+        }
+        CallbackConfig::Postgres(pg_config) => {
+            if let Some(targets) = &clients.postgres {
+                execute_postgres_callback(
+                    pool,
+                    &timer,
+                    pg_config,
+                    targets,
+                    instance_id,
+                    next_run_at(&timer),
+                )
+                .await
+            } else {
+                Err("Postgres callbacks not available (POSTGRES_CALLBACK_TABLES / POSTGRES_CALLBACK_CHANNELS not configured)"
+                    .to_string()
+                    .into())
             }
         }
     };
 
+    // Record this dispatch in the execution history
+    let (status, report, error) = match &result {
+        Ok(report) => (ExecutionStatus::Completed, report.clone(), None),
+        Err(err) => (
+            ExecutionStatus::Failed,
+            err.report.clone(),
+            Some(err.message.clone()),
+        ),
+    };
+    let execution = new_execution(&timer, instance_id, started_at, status, report, error);
+    if let Err(e) = db_record_execution(pool, &execution).await {
+        warn!("Failed to record execution for timer {}: {}", timer.id, e);
+    }
+
     // Update timer status based on result
     match result {
         Ok(_) => {
             info!("Callback completed successfully for timer {}", timer.id);
-            if let Err(e) = db_mark_completed(pool, timer.id).await {
-                warn!("Failed to mark timer as completed: {}", e);
+            if matches!(timer.callback_config, CallbackConfig::Postgres(_)) {
+                // Already completed (or rescheduled) in the delivery transaction
+            } else if let Some(next_at) = next_run_at(&timer) {
+                info!("Rescheduling recurring timer {} to {}", timer.id, next_at);
+                match db_schedule_next_occurrence(pool, timer.id, instance_id, next_at, None).await
+                {
+                    Ok(true) => {}
+                    Ok(false) => log_lost_claim(timer.id, instance_id),
+                    Err(e) => warn!("Failed to reschedule recurring timer: {}", e),
+                }
+            } else {
+                match db_mark_completed(pool, timer.id, instance_id).await {
+                    Ok(true) => {}
+                    Ok(false) => log_lost_claim(timer.id, instance_id),
+                    Err(e) => warn!("Failed to mark timer as completed: {}", e),
+                }
             }
         }
-        Err(error_msg) => {
+        Err(CallbackError {
+            message: error_msg,
+            permanent,
+            ..
+        }) => {
             warn!("Callback failed for timer {}: {}", timer.id, error_msg);
-            if let Err(e) = db_mark_failed(pool, timer.id, error_msg).await {
-                warn!("Failed to mark timer as failed: {}", e);
+
+            let attempts = timer.attempts + 1;
+            let retry_at = if permanent {
+                info!("Not retrying timer {}: permanent failure", timer.id);
+                None
+            } else {
+                next_retry_at(&timer, attempts)
+            };
+
+            if let Some(retry_at) = retry_at {
+                info!(
+                    "Retrying timer {} at {} (attempt {} failed)",
+                    timer.id, retry_at, attempts
+                );
+                match db_schedule_retry(pool, timer.id, instance_id, retry_at, error_msg).await {
+                    Ok(true) => {}
+                    Ok(false) => log_lost_claim(timer.id, instance_id),
+                    Err(e) => warn!("Failed to schedule retry for timer: {}", e),
+                }
+            } else {
+                finish_failed_occurrence(pool, &timer, instance_id, error_msg).await;
             }
         }
     }
 }
+
+/// Skip a claimed timer that is too late to fire under its catch-up policy
+///
+/// Records a missed execution; recurring timers move on to their next
+/// occurrence, one-shot timers are marked missed.
+pub async fn skip_missed_occurrence(
+    pool: &PgPool,
+    timer: Timer,
+    instance_id: &str,
+    lateness: chrono::Duration,
+) {
+    let error_msg = format!(
+        "missed: {}s late (catch_up_policy {})",
+        lateness.num_seconds(),
+        timer.catch_up_policy
+    );
+    warn!("Timer {} {}", timer.id, error_msg);
+
+    let execution = new_execution(
+        &timer,
+        instance_id,
+        Utc::now(),
+        ExecutionStatus::Missed,
+        CallbackReport::default(),
+        Some(error_msg.clone()),
+    );
+    if let Err(e) = db_record_execution(pool, &execution).await {
+        warn!("Failed to record execution for timer {}: {}", timer.id, e);
+    }
+
+    if let Some(next_at) = next_run_at(&timer) {
+        info!("Rescheduling recurring timer {} to {}", timer.id, next_at);
+        match db_schedule_next_occurrence(pool, timer.id, instance_id, next_at, Some(error_msg))
+            .await
+        {
+            Ok(true) => {}
+            Ok(false) => log_lost_claim(timer.id, instance_id),
+            Err(e) => warn!("Failed to reschedule recurring timer: {}", e),
+        }
+    } else {
+        match db_mark_missed(pool, timer.id, instance_id, error_msg).await {
+            Ok(true) => {}
+            Ok(false) => log_lost_claim(timer.id, instance_id),
+            Err(e) => warn!("Failed to mark timer as missed: {}", e),
+        }
+    }
+}
+
+/// Finish an occurrence that failed for good (no retries left)
+///
+/// Recurring timers move on to their next occurrence, keeping the error in
+/// last_error; one-shot timers (or ended schedules) are marked failed.
+pub async fn finish_failed_occurrence(
+    pool: &PgPool,
+    timer: &Timer,
+    instance_id: &str,
+    error_msg: String,
+) {
+    if let Some(next_at) = next_run_at(timer) {
+        info!("Rescheduling recurring timer {} to {}", timer.id, next_at);
+        match db_schedule_next_occurrence(pool, timer.id, instance_id, next_at, Some(error_msg))
+            .await
+        {
+            Ok(true) => {}
+            Ok(false) => log_lost_claim(timer.id, instance_id),
+            Err(e) => warn!("Failed to reschedule recurring timer: {}", e),
+        }
+    } else {
+        match db_mark_failed(pool, timer.id, instance_id, error_msg).await {
+            Ok(true) => {}
+            Ok(false) => log_lost_claim(timer.id, instance_id),
+            Err(e) => warn!("Failed to mark timer as failed: {}", e),
+        }
+    }
+}
+
+/// Log a status write skipped because this instance no longer holds the timer's claim
+///
+/// The timer was canceled, or its lease expired and it was requeued, while the
+/// callback was running.
+fn log_lost_claim(timer_id: Uuid, instance_id: &str) {
+    warn!(
+        "Timer {} is no longer executing under instance {}; leaving its status unchanged",
+        timer_id, instance_id
+    );
+}
+
+/// Build the execution history record of a dispatch that finished now
+fn new_execution(
+    timer: &Timer,
+    instance_id: &str,
+    started_at: DateTime<Utc>,
+    status: ExecutionStatus,
+    report: CallbackReport,
+    error: Option<String>,
+) -> TimerExecution {
+    let finished_at = Utc::now();
+
+    TimerExecution {
+        id: Uuid::new_v4(),
+        timer_id: timer.id,
+        occurrence: timer.occurrences + 1,
+        attempt: timer.attempts + 1,
+        scheduled_at: timer.occurrence_at,
+        started_at,
+        finished_at,
+        duration_ms: (finished_at - started_at).num_milliseconds(),
+        status,
+        http_status: report.http_status,
+        nats_ack: report.nats_ack,
+        response_body: report.response_body,
+        error,
+        instance_id: Some(instance_id.to_string()),
+    }
+}
+
+/// Next occurrence of a recurring timer, None for one-shot timers or ended schedules
+fn next_run_at(timer: &Timer) -> Option<DateTime<Utc>> {
+    let schedule = timer.schedule.as_ref()?;
+    next_occurrence(schedule, timer.occurrence_at, timer.occurrences + 1)
+}
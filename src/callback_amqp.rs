@@ -0,0 +1,162 @@
+//! AMQP callback execution module
+//! Publishes messages to RabbitMQ exchanges and waits for publisher confirms
+
+use crate::callback::{CallbackReport, CallbackResult};
+use crate::config::AmqpConfig;
+use crate::models::{AmqpCallback, Timer, DEFAULT_AMQP_CONFIRM_TIMEOUT_SECS};
+use lapin::options::{BasicPublishOptions, ConfirmSelectOptions};
+use lapin::publisher_confirm::Confirmation;
+use lapin::types::{AMQPValue, FieldTable};
+use lapin::{BasicProperties, Channel, Connection, ConnectionProperties};
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+/// Long-lived AMQP connection shared by all AMQP callbacks
+///
+/// Publishes go through one channel in confirm mode. A connection or channel
+/// closed by the broker is replaced on the next publish.
+pub struct AmqpPublisher {
+    uri: String,
+    session: Mutex<Option<(Connection, Channel)>>,
+}
+
+impl AmqpPublisher {
+    /// Connect to the broker and open the confirm channel
+    pub async fn connect(config: &AmqpConfig) -> lapin::Result<Self> {
+        let credentials = match (&config.user, &config.password) {
+            (Some(user), Some(password)) => format!(
+                "{}:{}@",
+                urlencoding::encode(user),
+                urlencoding::encode(password)
+            ),
+            _ => String::new(),
+        };
+        let uri = format!(
+            "amqp://{}{}:{}/{}",
+            credentials,
+            config.host,
+            config.port,
+            urlencoding::encode(&config.vhost)
+        );
+
+        let publisher = Self {
+            uri,
+            session: Mutex::new(None),
+        };
+        publisher.channel().await?;
+        Ok(publisher)
+    }
+
+    /// Open channel in confirm mode, reconnecting if the previous one was closed
+    async fn channel(&self) -> lapin::Result<Channel> {
+        let mut session = self.session.lock().await;
+        if let Some((connection, channel)) = session.as_ref() {
+            if connection.status().connected() && channel.status().connected() {
+                return Ok(channel.clone());
+            }
+        }
+
+        let connection = Connection::connect(&self.uri, ConnectionProperties::default()).await?;
+        let channel = connection.create_channel().await?;
+        channel
+            .confirm_select(ConfirmSelectOptions::default())
+            .await?;
+
+        *session = Some((connection, channel.clone()));
+        Ok(channel)
+    }
+}
+
+/// Execute AMQP callback for a timer
+///
+/// Publishes the payload to the exchange with the routing key and waits for
+/// the broker's publisher confirm. A nack, a missing confirm, or (with
+/// mandatory set) an unroutable message is a callback failure. The message id
+/// is the timer's delivery id so consumers can deduplicate retried publishes.
+pub async fn execute_amqp_callback(
+    timer: &Timer,
+    amqp_config: &AmqpCallback,
+    publisher: &AmqpPublisher,
+) -> CallbackResult {
+    // Build message payload
+    let payload = if let Some(payload_value) = &amqp_config.payload {
+        serde_json::to_vec(payload_value)
+            .map_err(|e| format!("Failed to serialize payload: {}", e))?
+    } else {
+        // Empty payload if none provided
+        Vec::new()
+    };
+
+    let mut properties = BasicProperties::default()
+        .with_content_type("application/json".into())
+        .with_message_id(timer.delivery_id().into())
+        .with_delivery_mode(if amqp_config.persistent { 2 } else { 1 });
+
+    // Add headers if present
+    if let Some(headers_obj) = amqp_config.headers.as_ref().and_then(|h| h.as_object()) {
+        let mut headers = FieldTable::default();
+        for (key, value) in headers_obj {
+            if let Some(val_str) = value.as_str() {
+                headers.insert(key.as_str().into(), AMQPValue::LongString(val_str.into()));
+            }
+        }
+        properties = properties.with_headers(headers);
+    }
+
+    let target = if amqp_config.exchange.is_empty() {
+        format!("default exchange ({})", amqp_config.routing_key)
+    } else {
+        format!("{} ({})", amqp_config.exchange, amqp_config.routing_key)
+    };
+    let timeout = Duration::from_secs(
+        amqp_config
+            .confirm_timeout_secs
+            .unwrap_or(DEFAULT_AMQP_CONFIRM_TIMEOUT_SECS),
+    );
+
+    let confirmation = async {
+        let channel = publisher.channel().await?;
+        let options = BasicPublishOptions {
+            mandatory: amqp_config.mandatory,
+            ..Default::default()
+        };
+        channel
+            .basic_publish(
+                &amqp_config.exchange,
+                &amqp_config.routing_key,
+                options,
+                &payload,
+                properties,
+            )
+            .await?
+            .await
+    };
+
+    let error = match tokio::time::timeout(timeout, confirmation).await {
+        Ok(Ok(Confirmation::Ack(None))) => {
+            info!(
+                "AMQP callback succeeded for timer {}: confirmed by {}",
+                timer.id, target
+            );
+            return Ok(CallbackReport::default());
+        }
+        Ok(Ok(Confirmation::Ack(Some(returned)))) => format!(
+            "AMQP publish to {} returned as unroutable: {} {}",
+            target, returned.reply_code, returned.reply_text
+        ),
+        Ok(Ok(Confirmation::Nack(_))) => format!("AMQP publish to {} nacked by broker", target),
+        Ok(Ok(Confirmation::NotRequested)) => {
+            format!("AMQP publish to {} was not confirmed", target)
+        }
+        Ok(Err(e)) => format!("AMQP publish to {} failed: {}", target, e),
+        Err(_) => format!(
+            "AMQP publish to {} not confirmed within {}s",
+            target,
+            timeout.as_secs()
+        ),
+    };
+
+    warn!("AMQP callback failed for timer {}: {}", timer.id, error);
+    Err(error.into())
+}
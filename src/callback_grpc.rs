@@ -0,0 +1,273 @@
+//! gRPC callback execution module
+//! Calls unary gRPC methods, building requests from registered protobuf descriptor sets
+
+use crate::callback::{truncate_body, CallbackError, CallbackReport, CallbackResult};
+use crate::config::GrpcConfig;
+use crate::models::{GrpcCallback, GrpcCode, Timer, DEFAULT_GRPC_DEADLINE_SECS};
+use anyhow::{Context, Result};
+use prost::Message;
+use prost_reflect::{DescriptorPool, DynamicMessage, MessageDescriptor, MethodDescriptor};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tonic::codec::{Codec, DecodeBuf, Decoder, EncodeBuf, Encoder};
+use tonic::codegen::http::uri::PathAndQuery;
+use tonic::transport::{Channel, ClientTlsConfig, Endpoint};
+use tonic::Status;
+use tracing::{info, warn};
+
+/// How long establishing a connection to a gRPC target may take
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Registered descriptors plus one long-lived channel per target
+///
+/// Channels connect lazily and reconnect on their own, so connections are
+/// reused across callbacks to the same target.
+pub struct GrpcClient {
+    descriptors: DescriptorPool,
+    channels: Mutex<HashMap<String, Channel>>,
+}
+
+impl GrpcClient {
+    /// Load the descriptor sets (protoc --include_imports --descriptor_set_out)
+    pub fn load(config: &GrpcConfig) -> Result<Self> {
+        let mut descriptors = DescriptorPool::new();
+        for path in &config.descriptor_sets {
+            let bytes = std::fs::read(path)
+                .with_context(|| format!("Failed to read descriptor set {}", path))?;
+            descriptors
+                .decode_file_descriptor_set(bytes.as_slice())
+                .with_context(|| format!("Invalid descriptor set {}", path))?;
+        }
+
+        Ok(Self {
+            descriptors,
+            channels: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Number of services that can be called
+    pub fn service_count(&self) -> usize {
+        self.descriptors.services().len()
+    }
+
+    /// Check that a callback's method is registered and unary, and that its payload maps to the request message
+    pub fn check(&self, grpc_config: &GrpcCallback) -> Result<(), String> {
+        let method = self.method(&grpc_config.method)?;
+        build_request(&method, grpc_config).map(|_| ())
+    }
+
+    /// Look up a unary method by its full name (package.Service/Method)
+    fn method(&self, name: &str) -> Result<MethodDescriptor, String> {
+        let (service, method) = name
+            .trim_start_matches('/')
+            .split_once('/')
+            .ok_or_else(|| format!("gRPC method '{}' must be package.Service/Method", name))?;
+
+        let method = self
+            .descriptors
+            .get_service_by_name(service)
+            .and_then(|service| service.methods().find(|m| m.name() == method))
+            .ok_or_else(|| {
+                format!(
+                    "gRPC method '{}' is not in a registered descriptor set",
+                    name
+                )
+            })?;
+
+        if method.is_client_streaming() || method.is_server_streaming() {
+            return Err(format!("gRPC method '{}' is not unary", name));
+        }
+        Ok(method)
+    }
+
+    /// Shared channel to a target, created on first use
+    fn channel(&self, target: &str) -> Result<Channel, tonic::transport::Error> {
+        let mut channels = self.channels.lock().unwrap();
+        if let Some(channel) = channels.get(target) {
+            return Ok(channel.clone());
+        }
+
+        let mut endpoint =
+            Endpoint::from_shared(target.to_string())?.connect_timeout(CONNECT_TIMEOUT);
+        if target.starts_with("https://") {
+            endpoint = endpoint.tls_config(ClientTlsConfig::new())?;
+        }
+        let channel = endpoint.connect_lazy();
+
+        channels.insert(target.to_string(), channel.clone());
+        Ok(channel)
+    }
+}
+
+/// Build the request message from the payload's protobuf JSON mapping
+fn build_request(
+    method: &MethodDescriptor,
+    grpc_config: &GrpcCallback,
+) -> Result<DynamicMessage, String> {
+    let payload = grpc_config
+        .payload
+        .clone()
+        .unwrap_or_else(|| serde_json::json!({}));
+
+    DynamicMessage::deserialize(method.input(), payload).map_err(|e| {
+        format!(
+            "callback.payload does not match {}: {}",
+            method.input().full_name(),
+            e
+        )
+    })
+}
+
+/// Execute gRPC callback for a timer
+///
+/// Calls the unary method with the payload as request message, metadata and
+/// a deadline. The response message (as JSON) or the status message is kept
+/// in the execution record; the status code decides success, retry or
+/// permanent failure according to success_codes and permanent_failure_codes.
+pub async fn execute_grpc_callback(
+    timer: &Timer,
+    grpc_config: &GrpcCallback,
+    client: &GrpcClient,
+) -> CallbackResult {
+    // Descriptors can change between deployments; a call that no longer
+    // resolves cannot succeed on retry
+    let (method, request_message) = match client
+        .method(&grpc_config.method)
+        .and_then(|method| build_request(&method, grpc_config).map(|message| (method, message)))
+    {
+        Ok(resolved) => resolved,
+        Err(error) => {
+            warn!("gRPC callback failed for timer {}: {}", timer.id, error);
+            return Err(CallbackError::permanent(error, CallbackReport::default()));
+        }
+    };
+
+    let channel = client
+        .channel(&grpc_config.target)
+        .map_err(|e| format!("Invalid gRPC target {}: {}", grpc_config.target, e))?;
+
+    let deadline = Duration::from_secs(
+        grpc_config
+            .deadline_secs
+            .unwrap_or(DEFAULT_GRPC_DEADLINE_SECS),
+    );
+
+    let mut request = tonic::Request::new(request_message);
+    request.set_timeout(deadline);
+
+    // Add metadata if present
+    if let Some(metadata) = grpc_config.metadata.as_ref().and_then(|m| m.as_object()) {
+        for (key, value) in metadata {
+            if let (Ok(key), Some(Ok(value))) = (
+                key.parse::<tonic::metadata::AsciiMetadataKey>(),
+                value.as_str().map(|v| v.parse()),
+            ) {
+                request.metadata_mut().insert(key, value);
+            }
+        }
+    }
+
+    let path = format!("/{}/{}", method.parent_service().full_name(), method.name());
+    let path = PathAndQuery::try_from(path)
+        .map_err(|e| format!("Invalid gRPC method {}: {}", grpc_config.method, e))?;
+    let codec = DynamicCodec(method.output());
+
+    let call = async move {
+        let mut grpc = tonic::client::Grpc::new(channel);
+        grpc.ready()
+            .await
+            .map_err(|e| Status::unavailable(format!("gRPC target not ready: {}", e)))?;
+        grpc.unary(request, path, codec).await
+    };
+
+    // The grpc-timeout header tells the server; this bounds the wait here
+    let result = match tokio::time::timeout(deadline, call).await {
+        Ok(result) => result,
+        Err(_) => Err(Status::deadline_exceeded("deadline exceeded")),
+    };
+
+    let (code, report) = match result {
+        Ok(response) => {
+            let body = serde_json::to_vec(response.get_ref()).unwrap_or_default();
+            (
+                GrpcCode::Ok,
+                CallbackReport {
+                    response_body: truncate_body(&body),
+                    ..Default::default()
+                },
+            )
+        }
+        Err(status) => (
+            GrpcCode::from(status.code()),
+            CallbackReport {
+                response_body: truncate_body(status.message().as_bytes()),
+                ..Default::default()
+            },
+        ),
+    };
+
+    if grpc_config.is_success(code) {
+        info!(
+            "gRPC callback succeeded for timer {}: {} {}",
+            timer.id, grpc_config.method, code
+        );
+        Ok(report)
+    } else if grpc_config.is_permanent_failure(code) {
+        let error = format!(
+            "gRPC {} from {} (permanent failure)",
+            code, grpc_config.method
+        );
+        warn!("gRPC callback failed for timer {}: {}", timer.id, error);
+        Err(CallbackError::permanent(error, report))
+    } else {
+        let error = format!("gRPC {} from {}", code, grpc_config.method);
+        warn!("gRPC callback failed for timer {}: {}", timer.id, error);
+        Err(CallbackError::with_report(error, report))
+    }
+}
+
+/// Codec for messages described at runtime
+struct DynamicCodec(MessageDescriptor);
+
+struct DynamicEncoder;
+
+struct DynamicDecoder(MessageDescriptor);
+
+impl Codec for DynamicCodec {
+    type Encode = DynamicMessage;
+    type Decode = DynamicMessage;
+    type Encoder = DynamicEncoder;
+    type Decoder = DynamicDecoder;
+
+    fn encoder(&mut self) -> Self::Encoder {
+        DynamicEncoder
+    }
+
+    fn decoder(&mut self) -> Self::Decoder {
+        DynamicDecoder(self.0.clone())
+    }
+}
+
+impl Encoder for DynamicEncoder {
+    type Item = DynamicMessage;
+    type Error = Status;
+
+    fn encode(&mut self, item: Self::Item, dst: &mut EncodeBuf<'_>) -> Result<(), Self::Error> {
+        item.encode(dst)
+            .map_err(|e| Status::internal(format!("Failed to encode request: {}", e)))
+    }
+}
+
+impl Decoder for DynamicDecoder {
+    type Item = DynamicMessage;
+    type Error = Status;
+
+    fn decode(&mut self, src: &mut DecodeBuf<'_>) -> Result<Option<Self::Item>, Self::Error> {
+        let mut message = DynamicMessage::new(self.0.clone());
+        message
+            .merge(src)
+            .map_err(|e| Status::internal(format!("Failed to decode response: {}", e)))?;
+        Ok(Some(message))
+    }
+}
@@ -1,30 +1,65 @@
 //! HTTP callback execution module
-//! Handles HTTP POST requests to external webhook URLs
+//! Handles HTTP requests to external webhook URLs
 
-use crate::models::{HTTPCallback, Timer};
-use reqwest::Client;
+use crate::callback::{
+    truncate_body, CallbackError, CallbackReport, CallbackResult, MAX_RESPONSE_BODY_BYTES,
+};
+use crate::config::{HttpClientConfig, HttpVersion};
+use crate::models::{HTTPCallback, Timer, DEFAULT_HTTP_TIMEOUT_SECS};
+use crate::signing::{signature_header, SIGNATURE_HEADER, TIMESTAMP_HEADER};
+use chrono::Utc;
+use reqwest::{Client, Response};
 use std::time::Duration;
 use tracing::{info, warn};
 
+/// Build the long-lived HTTP client shared by all HTTP callbacks
+///
+/// Connections and TLS sessions are pooled across callbacks; request timeouts
+/// are set per callback.
+pub fn build_http_client(config: &HttpClientConfig) -> reqwest::Result<Client> {
+    let mut builder = Client::builder()
+        .user_agent("timer-platform/0.1.0")
+        .pool_max_idle_per_host(config.pool_max_idle_per_host)
+        .pool_idle_timeout(Duration::from_secs(config.pool_idle_timeout_secs))
+        .connect_timeout(Duration::from_secs(config.connect_timeout_secs));
+
+    if config.tcp_keepalive_secs > 0 {
+        builder = builder.tcp_keepalive(Duration::from_secs(config.tcp_keepalive_secs));
+    }
+
+    builder = match config.http_version {
+        HttpVersion::Auto => builder,
+        HttpVersion::Http1 => builder.http1_only(),
+        HttpVersion::Http2 => builder.http2_prior_knowledge(),
+    };
+
+    if let Some(proxy_url) = &config.proxy_url {
+        builder = builder.proxy(reqwest::Proxy::all(proxy_url)?);
+    }
+
+    builder.build()
+}
+
 /// Execute HTTP callback for a timer
 ///
-/// Builds and sends an HTTP POST request with custom headers and JSON payload.
-/// Returns Ok(()) on 2xx response, Err with error message otherwise.
+/// Builds and sends an HTTP request (POST by default) with custom headers and JSON payload.
+/// The request is signed with the timer's signing secrets, or the global ones
+/// (`signing_secrets`) if the timer has none; unsigned when neither is set.
+/// Returns the response status and (truncated) body. Responses outside the
+/// accepted status codes (default any 2xx) are errors; permanent failure codes
+/// are reported as permanent so the retry policy is skipped.
 pub async fn execute_http_callback(
     timer: &Timer,
     http_config: &HTTPCallback,
-) -> Result<(), String> {
-    // Build HTTP client with 30s timeout
-    let client = Client::builder()
-        .timeout(Duration::from_secs(30))
-        .build()
-        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
-
-    // Build request
+    client: &Client,
+    signing_secrets: &[String],
+) -> CallbackResult {
+    // Build request with the timer's timeout (default 30s)
+    let timeout = http_config.timeout_secs.unwrap_or(DEFAULT_HTTP_TIMEOUT_SECS);
     let mut request = client
-        .post(&http_config.url)
-        .header("Content-Type", "application/json")
-        .header("User-Agent", "timer-platform/0.1.0");
+        .request(http_config.method.into(), &http_config.url)
+        .timeout(Duration::from_secs(timeout))
+        .header("Content-Type", "application/json");
 
     // Add custom headers if present
     if let Some(headers) = &http_config.headers {
@@ -37,31 +72,73 @@ pub async fn execute_http_callback(
         }
     }
 
+    // Serialize payload ourselves so the signature covers the exact bytes sent
+    let body = match &http_config.payload {
+        Some(payload) => serde_json::to_vec(payload)
+            .map_err(|e| format!("Failed to serialize payload: {}", e))?,
+        None => Vec::new(),
+    };
+
+    // Sign timestamp and body (timer secrets take precedence over global ones)
+    let secrets = http_config
+        .signing_secrets
+        .as_deref()
+        .unwrap_or(signing_secrets);
+    if !secrets.is_empty() {
+        let timestamp = Utc::now().timestamp();
+        request = request
+            .header(TIMESTAMP_HEADER, timestamp.to_string())
+            .header(SIGNATURE_HEADER, signature_header(secrets, timestamp, &body));
+    }
+
     // Add payload if present
-    if let Some(payload) = &http_config.payload {
-        request = request.json(payload);
+    if http_config.payload.is_some() {
+        request = request.body(body);
     }
 
     // Execute request
     match request.send().await {
         Ok(response) => {
-            if response.status().is_success() {
-                info!(
-                    "HTTP callback succeeded for timer {}: {}",
-                    timer.id,
-                    response.status()
-                );
-                Ok(())
+            let status = response.status();
+            let report = CallbackReport {
+                http_status: Some(status.as_u16() as i32),
+                response_body: read_truncated_body(response).await,
+                ..Default::default()
+            };
+
+            if http_config.is_success(status.as_u16()) {
+                info!("HTTP callback succeeded for timer {}: {}", timer.id, status);
+                Ok(report)
+            } else if http_config.is_permanent_failure(status.as_u16()) {
+                let error = format!("HTTP {} from {} (permanent failure)", status, http_config.url);
+                warn!("HTTP callback failed for timer {}: {}", timer.id, error);
+                Err(CallbackError::permanent(error, report))
             } else {
-                let error = format!("HTTP {} from {}", response.status(), http_config.url);
+                let error = format!("HTTP {} from {}", status, http_config.url);
                 warn!("HTTP callback failed for timer {}: {}", timer.id, error);
-                Err(error)
+                Err(CallbackError::with_report(error, report))
             }
         }
         Err(e) => {
             let error = format!("HTTP request failed: {}", e);
             warn!("HTTP callback failed for timer {}: {}", timer.id, error);
-            Err(error)
+            Err(error.into())
+        }
+    }
+}
+
+/// Read at most MAX_RESPONSE_BODY_BYTES of the response body
+///
+/// Stops reading once the limit is reached so large responses are not buffered.
+async fn read_truncated_body(mut response: Response) -> Option<String> {
+    let mut body = Vec::new();
+
+    while let Ok(Some(chunk)) = response.chunk().await {
+        body.extend_from_slice(&chunk);
+        if body.len() >= MAX_RESPONSE_BODY_BYTES {
+            break;
         }
     }
+
+    truncate_body(&body)
 }
@@ -0,0 +1,104 @@
+//! Kafka callback execution module
+//! Produces records to Kafka topics and waits for the broker's delivery report
+
+use crate::callback::{CallbackReport, CallbackResult};
+use crate::config::KafkaConfig;
+use crate::models::{KafkaCallback, Timer};
+use rdkafka::config::ClientConfig;
+use rdkafka::error::KafkaResult;
+use rdkafka::message::{Header, OwnedHeaders};
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// How long a send waits for room in the producer queue
+const QUEUE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Build the long-lived Kafka producer shared by all Kafka callbacks
+///
+/// Records are acknowledged by all in-sync replicas (acks=all); delivery is
+/// retried by the producer until message.timeout.ms expires.
+pub fn build_kafka_producer(config: &KafkaConfig) -> KafkaResult<FutureProducer> {
+    let mut client_config = ClientConfig::new();
+    client_config
+        .set("bootstrap.servers", &config.brokers)
+        .set("client.id", &config.client_id)
+        .set("acks", "all")
+        .set("message.timeout.ms", config.message_timeout_ms.to_string());
+
+    // SASL/PLAIN authentication if credentials provided
+    if let (Some(user), Some(password)) = (&config.user, &config.password) {
+        client_config
+            .set("security.protocol", "sasl_plaintext")
+            .set("sasl.mechanism", "PLAIN")
+            .set("sasl.username", user)
+            .set("sasl.password", password);
+    }
+
+    client_config.create()
+}
+
+/// Execute Kafka callback for a timer
+///
+/// Produces one record with optional key, headers and partition hint, and
+/// waits for the delivery report. The partition and offset are kept in the
+/// execution record; a failed delivery is a callback failure.
+pub async fn execute_kafka_callback(
+    timer: &Timer,
+    kafka_config: &KafkaCallback,
+    producer: &FutureProducer,
+) -> CallbackResult {
+    // Build record payload
+    let payload = if let Some(payload_value) = &kafka_config.payload {
+        serde_json::to_vec(payload_value)
+            .map_err(|e| format!("Failed to serialize payload: {}", e))?
+    } else {
+        // Empty payload if none provided
+        Vec::new()
+    };
+
+    let mut record = FutureRecord::<str, [u8]>::to(&kafka_config.topic).payload(&payload);
+
+    if let Some(key) = &kafka_config.key {
+        record = record.key(key.as_str());
+    }
+
+    if let Some(partition) = kafka_config.partition {
+        record = record.partition(partition);
+    }
+
+    // Add headers if present
+    if let Some(headers_obj) = kafka_config.headers.as_ref().and_then(|h| h.as_object()) {
+        let mut headers = OwnedHeaders::new();
+        for (key, value) in headers_obj {
+            if let Some(val_str) = value.as_str() {
+                headers = headers.insert(Header {
+                    key,
+                    value: Some(val_str),
+                });
+            }
+        }
+        record = record.headers(headers);
+    }
+
+    // Produce and wait for the delivery report
+    match producer.send(record, QUEUE_TIMEOUT).await {
+        Ok((partition, offset)) => {
+            info!(
+                "Kafka callback succeeded for timer {}: {} partition {} offset {}",
+                timer.id, kafka_config.topic, partition, offset
+            );
+            Ok(CallbackReport {
+                response_body: Some(
+                    serde_json::json!({ "partition": partition, "offset": offset }).to_string(),
+                ),
+                ..Default::default()
+            })
+        }
+        Err((e, _)) => {
+            let error = format!("Kafka delivery to {} failed: {}", kafka_config.topic, e);
+            warn!("Kafka callback failed for timer {}: {}", timer.id, error);
+            Err(error.into())
+        }
+    }
+}
@@ -1,19 +1,27 @@
 //! NATS callback execution module
-//! Handles fire-and-forget message publishing to NATS topics
+//! Handles message publishing to NATS topics (core NATS, JetStream with ack, or request/reply)
 
-use crate::models::{NATSCallback, Timer};
+use crate::callback::{truncate_body, CallbackError, CallbackReport, CallbackResult};
+use crate::models::{
+    NATSCallback, NatsMode, Timer, DEFAULT_NATS_ACK_TIMEOUT_SECS, DEFAULT_NATS_REPLY_TIMEOUT_SECS,
+};
+use async_nats::jetstream::context::Publish;
 use async_nats::Client as NatsClient;
+use std::time::Duration;
 use tracing::{info, warn};
 
 /// Execute NATS callback for a timer
 ///
 /// Publishes a message to the specified NATS topic with optional headers.
-/// Returns Ok(()) on successful publish, Err with error message otherwise.
+/// In publish mode the message is fire-and-forget; in jetstream mode the
+/// callback only succeeds once a stream acknowledges the message; in request
+/// mode it succeeds once a consumer replies and the reply meets reply_success.
+/// Returns Ok on success, Err with error message otherwise.
 pub async fn execute_nats_callback(
     timer: &Timer,
     nats_config: &NATSCallback,
     nats_client: &NatsClient,
-) -> Result<(), String> {
+) -> CallbackResult {
     // Build message payload
     let payload = if let Some(payload_value) = &nats_config.payload {
         serde_json::to_vec(payload_value)
@@ -47,7 +55,25 @@ pub async fn execute_nats_callback(
         None
     };
 
-    // Publish message (fire-and-forget)
+    match nats_config.mode {
+        NatsMode::Publish => publish_core(timer, nats_client, subject, headers, payload).await,
+        NatsMode::Jetstream => {
+            publish_jetstream(timer, nats_config, nats_client, subject, headers, payload).await
+        }
+        NatsMode::Request => {
+            request_reply(timer, nats_config, nats_client, subject, headers, payload).await
+        }
+    }
+}
+
+/// Core NATS publish (fire-and-forget)
+async fn publish_core(
+    timer: &Timer,
+    nats_client: &NatsClient,
+    subject: String,
+    headers: Option<async_nats::HeaderMap>,
+    payload: Vec<u8>,
+) -> CallbackResult {
     let result = if let Some(hdrs) = headers {
         nats_client
             .publish_with_headers(subject.clone(), hdrs, payload.into())
@@ -62,12 +88,139 @@ pub async fn execute_nats_callback(
                 "NATS callback succeeded for timer {}: published to {}",
                 timer.id, subject
             );
-            Ok(())
+            Ok(CallbackReport::default())
         }
         Err(e) => {
             let error = format!("NATS publish failed: {}", e);
             warn!("NATS callback failed for timer {}: {}", timer.id, error);
-            Err(error)
+            Err(error.into())
+        }
+    }
+}
+
+/// JetStream publish that waits for the stream's ack
+///
+/// Nats-Msg-Id is set to the timer's delivery id so a retried publish (e.g.
+/// after a lost ack) is deduplicated by the stream, while every run of a
+/// recurring timer is still delivered.
+async fn publish_jetstream(
+    timer: &Timer,
+    nats_config: &NATSCallback,
+    nats_client: &NatsClient,
+    subject: String,
+    headers: Option<async_nats::HeaderMap>,
+    payload: Vec<u8>,
+) -> CallbackResult {
+    let mut jetstream = async_nats::jetstream::new(nats_client.clone());
+    jetstream.set_timeout(Duration::from_secs(
+        nats_config
+            .ack_timeout_secs
+            .unwrap_or(DEFAULT_NATS_ACK_TIMEOUT_SECS),
+    ));
+
+    let mut publish = Publish::build().payload(payload.into());
+    if let Some(hdrs) = headers {
+        publish = publish.headers(hdrs);
+    }
+    let publish = publish.message_id(timer.delivery_id());
+
+    let ack = match jetstream.send_publish(subject.clone(), publish).await {
+        Ok(ack_future) => ack_future.await,
+        Err(e) => Err(e),
+    };
+
+    match ack {
+        Ok(ack) => {
+            info!(
+                "NATS callback succeeded for timer {}: stored in stream {} (seq {}{})",
+                timer.id,
+                ack.stream,
+                ack.sequence,
+                if ack.duplicate { ", duplicate" } else { "" }
+            );
+            Ok(CallbackReport {
+                nats_ack: Some(true),
+                response_body: Some(
+                    serde_json::json!({
+                        "stream": ack.stream,
+                        "seq": ack.sequence,
+                        "duplicate": ack.duplicate,
+                    })
+                    .to_string(),
+                ),
+                ..Default::default()
+            })
         }
+        Err(e) => {
+            let error = format!("JetStream publish to {} not acknowledged: {}", subject, e);
+            warn!("NATS callback failed for timer {}: {}", timer.id, error);
+            Err(CallbackError::with_report(
+                error,
+                CallbackReport {
+                    nats_ack: Some(false),
+                    ..Default::default()
+                },
+            ))
+        }
+    }
+}
+
+/// NATS request that waits for a consumer's reply
+///
+/// The reply body is kept in the execution record, so failed conditions show
+/// what the consumer actually answered.
+async fn request_reply(
+    timer: &Timer,
+    nats_config: &NATSCallback,
+    nats_client: &NatsClient,
+    subject: String,
+    headers: Option<async_nats::HeaderMap>,
+    payload: Vec<u8>,
+) -> CallbackResult {
+    let timeout = Duration::from_secs(
+        nats_config
+            .reply_timeout_secs
+            .unwrap_or(DEFAULT_NATS_REPLY_TIMEOUT_SECS),
+    );
+
+    let mut request = async_nats::Request::new()
+        .payload(payload.into())
+        .timeout(Some(timeout));
+    if let Some(hdrs) = headers {
+        request = request.headers(hdrs);
+    }
+
+    let reply = match nats_client.send_request(subject.clone(), request).await {
+        Ok(reply) => reply,
+        Err(e) => {
+            let error = format!("NATS request to {} got no reply: {}", subject, e);
+            warn!("NATS callback failed for timer {}: {}", timer.id, error);
+            return Err(CallbackError::with_report(
+                error,
+                CallbackReport {
+                    nats_ack: Some(false),
+                    ..Default::default()
+                },
+            ));
+        }
+    };
+
+    let report = CallbackReport {
+        nats_ack: Some(true),
+        response_body: truncate_body(&reply.payload),
+        ..Default::default()
+    };
+
+    let condition = nats_config.reply_success.clone().unwrap_or_default();
+    if condition.is_met(&reply.payload) {
+        info!(
+            "NATS callback succeeded for timer {}: {} replied",
+            timer.id, subject
+        );
+        Ok(report)
+    } else {
+        let error = format!("NATS reply from {} does not meet reply_success", subject);
+        warn!("NATS callback failed for timer {}: {}", timer.id, error);
+        Err(CallbackError::with_report(error, report))
     }
 }
@@ -0,0 +1,183 @@
+//! Postgres callback execution module
+//! Inserts into outbox tables or runs pg_notify in the transaction that completes the timer
+
+use crate::callback::{CallbackError, CallbackReport, CallbackResult};
+use crate::config::PostgresCallbackConfig;
+use crate::db::{db_mark_completed, db_schedule_next_occurrence};
+use crate::models::{PostgresCallback, PostgresMode, Timer};
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use tracing::{info, warn};
+
+/// Tables and channels Postgres callbacks may write to
+///
+/// Callbacks run with the service's own database role, so only targets listed
+/// in POSTGRES_CALLBACK_TABLES / POSTGRES_CALLBACK_CHANNELS are allowed.
+#[derive(Debug, Clone)]
+pub struct PostgresTargets {
+    tables: Vec<String>,
+    channels: Vec<String>,
+}
+
+impl PostgresTargets {
+    pub fn new(config: &PostgresCallbackConfig) -> Self {
+        Self {
+            tables: config.tables.clone(),
+            channels: config.channels.clone(),
+        }
+    }
+
+    /// Check that a callback's table or channel is allowed
+    pub fn check(&self, pg_config: &PostgresCallback) -> Result<(), String> {
+        match pg_config.mode {
+            PostgresMode::Outbox => {
+                let table = pg_config.table.as_deref().unwrap_or_default();
+                if !self.tables.iter().any(|allowed| allowed == table) {
+                    return Err(format!(
+                        "Postgres callback table '{}' is not in POSTGRES_CALLBACK_TABLES",
+                        table
+                    ));
+                }
+            }
+            PostgresMode::Notify => {
+                let channel = pg_config.channel.as_deref().unwrap_or_default();
+                if !self.channels.iter().any(|allowed| allowed == channel) {
+                    return Err(format!(
+                        "Postgres callback channel '{}' is not in POSTGRES_CALLBACK_CHANNELS",
+                        channel
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Quote a (optionally schema-qualified) table name as SQL identifiers
+///
+/// Returns None for names that are not plain identifiers; allowed tables are
+/// validated the same way at startup.
+pub fn quote_table_name(table: &str) -> Option<String> {
+    let parts: Vec<&str> = table.split('.').collect();
+    let valid = parts.len() <= 2
+        && parts.iter().all(|part| {
+            part.chars()
+                .next()
+                .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+                && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        });
+    if !valid {
+        return None;
+    }
+
+    Some(
+        parts
+            .iter()
+            .map(|part| format!("\"{}\"", part))
+            .collect::<Vec<_>>()
+            .join("."),
+    )
+}
+
+/// Execute Postgres callback for a timer
+///
+/// Writes the outbox row (timer_id, payload) or sends the notification, and
+/// marks the timer completed (or reschedules it to `next_at` for recurring
+/// timers) in the same transaction, so delivery and completion commit or roll
+/// back together. The status update runs first and only applies while
+/// `instance_id` still holds the claim; otherwise nothing is delivered. The
+/// caller must not update the timer status again on success.
+pub async fn execute_postgres_callback(
+    pool: &PgPool,
+    timer: &Timer,
+    pg_config: &PostgresCallback,
+    targets: &PostgresTargets,
+    instance_id: &str,
+    next_at: Option<DateTime<Utc>>,
+) -> CallbackResult {
+    // Allowed targets can change between deployments; a callback to a target
+    // that is no longer allowed cannot succeed on retry
+    if let Err(error) = targets.check(pg_config) {
+        warn!("Postgres callback failed for timer {}: {}", timer.id, error);
+        return Err(CallbackError::permanent(error, CallbackReport::default()));
+    }
+
+    let result = async {
+        let mut tx = pool.begin().await?;
+
+        // Lock the row and check the claim before delivering anything
+        let claimed = match next_at {
+            Some(next_at) => {
+                db_schedule_next_occurrence(&mut *tx, timer.id, instance_id, next_at, None).await?
+            }
+            None => db_mark_completed(&mut *tx, timer.id, instance_id).await?,
+        };
+        if !claimed {
+            tx.rollback().await?;
+            return anyhow::Ok(false);
+        }
+
+        match pg_config.mode {
+            PostgresMode::Outbox => {
+                let table = pg_config.table.as_deref().unwrap_or_default();
+                let quoted = quote_table_name(table)
+                    .ok_or_else(|| anyhow::anyhow!("invalid table name '{}'", table))?;
+                sqlx::query(&format!(
+                    "INSERT INTO {} (timer_id, payload) VALUES ($1, $2)",
+                    quoted
+                ))
+                .bind(timer.id)
+                .bind(&pg_config.payload)
+                .execute(&mut *tx)
+                .await?;
+            }
+            PostgresMode::Notify => {
+                let payload = pg_config
+                    .payload
+                    .as_ref()
+                    .map(|payload| payload.to_string())
+                    .unwrap_or_default();
+                sqlx::query("SELECT pg_notify($1, $2)")
+                    .bind(pg_config.channel.as_deref().unwrap_or_default())
+                    .bind(payload)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+        }
+
+        tx.commit().await?;
+        anyhow::Ok(true)
+    }
+    .await;
+
+    let target = match pg_config.mode {
+        PostgresMode::Outbox => pg_config.table.as_deref().unwrap_or_default(),
+        PostgresMode::Notify => pg_config.channel.as_deref().unwrap_or_default(),
+    };
+
+    match result {
+        Ok(true) => {
+            info!(
+                "Postgres callback succeeded for timer {}: delivered to {}",
+                timer.id, target
+            );
+            Ok(CallbackReport::default())
+        }
+        Ok(false) => {
+            let error = format!(
+                "not delivered to {}: timer is no longer claimed by this instance",
+                target
+            );
+            warn!(
+                "Postgres callback skipped for timer {}: {}",
+                timer.id, error
+            );
+            Err(CallbackError::permanent(error, CallbackReport::default()))
+        }
+        Err(e) => {
+            let error = format!("Postgres delivery to {} failed: {}", target, e);
+            warn!("Postgres callback failed for timer {}: {}", timer.id, error);
+            Err(error.into())
+        }
+    }
+}
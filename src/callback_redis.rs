@@ -0,0 +1,113 @@
+//! Redis callback execution module
+//! Adds entries to Redis/Valkey streams (XADD) or publishes to pub/sub channels
+
+use crate::callback::{CallbackReport, CallbackResult};
+use crate::config::RedisConfig;
+use crate::models::{RedisCallback, RedisMode, Timer};
+use redis::aio::ConnectionManager;
+use redis::{ConnectionAddr, ConnectionInfo, RedisConnectionInfo, RedisResult};
+use tracing::{info, warn};
+
+/// Connect the long-lived Redis connection shared by all Redis callbacks
+///
+/// The connection manager multiplexes commands over one connection and
+/// reconnects on its own after the server goes away.
+pub async fn connect_redis(config: &RedisConfig) -> RedisResult<ConnectionManager> {
+    let info = ConnectionInfo {
+        addr: ConnectionAddr::Tcp(config.host.clone(), config.port),
+        redis: RedisConnectionInfo {
+            db: config.db,
+            username: config.user.clone(),
+            password: config.password.clone(),
+        },
+    };
+
+    let client = redis::Client::open(info)?;
+    ConnectionManager::new(client).await
+}
+
+/// Execute Redis callback for a timer
+///
+/// In stream mode the payload is added as the "payload" field of a new stream
+/// entry (plus any extra fields), optionally trimming the stream; in publish
+/// mode it is published to the channel. The entry id or the number of
+/// receiving subscribers is kept in the execution record.
+pub async fn execute_redis_callback(
+    timer: &Timer,
+    redis_config: &RedisCallback,
+    mut connection: ConnectionManager,
+) -> CallbackResult {
+    // Build message payload
+    let payload = if let Some(payload_value) = &redis_config.payload {
+        serde_json::to_string(payload_value)
+            .map_err(|e| format!("Failed to serialize payload: {}", e))?
+    } else {
+        // Empty payload if none provided
+        String::new()
+    };
+
+    match redis_config.mode {
+        RedisMode::Stream => {
+            let mut command = redis::cmd("XADD");
+            command.arg(&redis_config.key);
+            if let Some(maxlen) = redis_config.maxlen {
+                command.arg("MAXLEN").arg("~").arg(maxlen);
+            }
+            command.arg("*").arg("payload").arg(&payload);
+
+            // Add extra entry fields if present
+            if let Some(fields) = redis_config.fields.as_ref().and_then(|f| f.as_object()) {
+                for (key, value) in fields {
+                    if let Some(val_str) = value.as_str() {
+                        command.arg(key).arg(val_str);
+                    }
+                }
+            }
+
+            match command.query_async::<_, String>(&mut connection).await {
+                Ok(entry_id) => {
+                    info!(
+                        "Redis callback succeeded for timer {}: added {} to stream {}",
+                        timer.id, entry_id, redis_config.key
+                    );
+                    Ok(CallbackReport {
+                        response_body: Some(serde_json::json!({ "id": entry_id }).to_string()),
+                        ..Default::default()
+                    })
+                }
+                Err(e) => {
+                    let error = format!("Redis XADD to {} failed: {}", redis_config.key, e);
+                    warn!("Redis callback failed for timer {}: {}", timer.id, error);
+                    Err(error.into())
+                }
+            }
+        }
+        RedisMode::Publish => {
+            let result = redis::cmd("PUBLISH")
+                .arg(&redis_config.key)
+                .arg(&payload)
+                .query_async::<_, i64>(&mut connection)
+                .await;
+
+            match result {
+                Ok(receivers) => {
+                    info!(
+                        "Redis callback succeeded for timer {}: published to {} ({} receivers)",
+                        timer.id, redis_config.key, receivers
+                    );
+                    Ok(CallbackReport {
+                        response_body: Some(
+                            serde_json::json!({ "receivers": receivers }).to_string(),
+                        ),
+                        ..Default::default()
+                    })
+                }
+                Err(e) => {
+                    let error = format!("Redis PUBLISH to {} failed: {}", redis_config.key, e);
+                    warn!("Redis callback failed for timer {}: {}", timer.id, error);
+                    Err(error.into())
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,87 @@
+//! Timer change listener module
+//! Applies timers created, updated or canceled through the API, or rescheduled after a dispatch (on any replica), to the local queue
+
+use sqlx::postgres::{PgListener, PgNotification};
+use sqlx::PgPool;
+use tokio::time::{sleep, Duration};
+
+use crate::db::{db_get_timer, db_load_near_term_timers, TIMER_CHANGES_CHANNEL};
+use crate::models::{TimerCache, TimerChange, TimerChangeOp};
+
+/// Delay before reconnecting after the listener connection fails
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// LISTEN on the timer_changes channel and keep the queue in sync
+///
+/// Notifications sent while the listener is disconnected are lost, so the
+/// queue is reloaded from the database every time the listener (re)connects.
+pub async fn run_change_listener(pool: PgPool, cache: TimerCache) {
+    loop {
+        let mut listener = match PgListener::connect_with(&pool).await {
+            Ok(listener) => listener,
+            Err(err) => {
+                tracing::warn!("Timer change listener connection failed: {}", err);
+                sleep(RECONNECT_DELAY).await;
+                continue;
+            }
+        };
+
+        if let Err(err) = listener.listen(TIMER_CHANGES_CHANNEL).await {
+            tracing::warn!("Failed to LISTEN on {}: {}", TIMER_CHANGES_CHANNEL, err);
+            sleep(RECONNECT_DELAY).await;
+            continue;
+        }
+
+        match db_load_near_term_timers(&pool).await {
+            Ok(timers) => cache.replace_all(timers),
+            Err(err) => tracing::warn!("Failed to load near-term timers: {}", err),
+        }
+
+        tracing::info!("Listening for timer changes on {}", TIMER_CHANGES_CHANNEL);
+
+        loop {
+            match listener.try_recv().await {
+                Ok(Some(notification)) => apply_change(&pool, &cache, &notification).await,
+                Ok(None) => {
+                    tracing::warn!("Timer change listener connection lost, reconnecting");
+                    break;
+                }
+                Err(err) => {
+                    tracing::warn!("Timer change listener failed: {}", err);
+                    break;
+                }
+            }
+        }
+
+        sleep(RECONNECT_DELAY).await;
+    }
+}
+
+/// Apply one notification to the queue
+async fn apply_change(pool: &PgPool, cache: &TimerCache, notification: &PgNotification) {
+    let change = match serde_json::from_str::<TimerChange>(notification.payload()) {
+        Ok(change) => change,
+        Err(err) => {
+            tracing::warn!(
+                "Ignoring invalid timer change {:?}: {}",
+                notification.payload(),
+                err
+            );
+            return;
+        }
+    };
+
+    match change.op {
+        TimerChangeOp::Canceled => cache.remove(change.id),
+        TimerChangeOp::Created | TimerChangeOp::Updated => {
+            // Re-read the row: it may have changed again since the notification was sent
+            match db_get_timer(pool, change.id).await {
+                Ok(Some(timer)) => cache.apply(&timer),
+                Ok(None) => cache.remove(change.id),
+                Err(err) => {
+                    tracing::warn!("Failed to load changed timer {}: {}", change.id, err);
+                }
+            }
+        }
+    }
+}
@@ -1,14 +1,99 @@
 use anyhow::{anyhow, Context, Result};
 use std::env;
 
+use crate::models::CatchUpPolicy;
+
 #[derive(Debug, Clone)]
 pub struct Config {
     pub database_url: String,
     pub api_key: String,
     pub port: u16,
     pub rust_log: String,
+    /// Identifier of this scheduler instance (recorded with every execution)
+    pub instance_id: String,
+    /// Execution lease duration; must outlive the slowest callback
+    pub lease_duration_secs: i64,
+    /// Attempts after which an expired lease marks the timer failed instead of pending
+    pub lease_max_attempts: i32,
+    /// Scheduler replication mode (SCHEDULER_MODE=claim|leader)
+    pub scheduler_mode: SchedulerMode,
+    /// Catch-up policy for timers created without one
+    pub default_catch_up_policy: CatchUpPolicy,
+    /// Allowed lateness for fire_within timers created without max_lateness_secs
+    pub default_max_lateness_secs: i32,
+    /// Maximum number of callbacks dispatched at once by this instance
+    pub max_concurrent_callbacks: usize,
+    /// Maximum number of concurrent HTTP callbacks to the same host
+    pub max_concurrent_per_host: usize,
+    /// Secrets signing HTTP callbacks (current first, then the previous one during rotation)
+    pub callback_signing_secrets: Vec<String>,
+    /// Shared HTTP client settings for HTTP callbacks
+    pub http_client: HttpClientConfig,
     /// Optional NATS configuration for pub/sub callbacks
     pub nats_config: Option<NatsConfig>,
+    /// Optional Kafka configuration for Kafka callbacks
+    pub kafka_config: Option<KafkaConfig>,
+    /// Optional Redis configuration for stream and pub/sub callbacks
+    pub redis_config: Option<RedisConfig>,
+    /// Optional AMQP configuration for RabbitMQ callbacks
+    pub amqp_config: Option<AmqpConfig>,
+    /// Optional gRPC configuration for gRPC callbacks
+    pub grpc_config: Option<GrpcConfig>,
+    /// Optional allowed targets for Postgres callbacks
+    pub postgres_callbacks: Option<PostgresCallbackConfig>,
+}
+
+/// How scheduler replicas share work
+#[derive(Debug, Clone, PartialEq)]
+pub enum SchedulerMode {
+    /// Every replica runs the scheduler; due timers are claimed row by row
+    Claim,
+    /// Only the replica holding the Postgres advisory lock runs the scheduler
+    Leader,
+}
+
+impl std::fmt::Display for SchedulerMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SchedulerMode::Claim => write!(f, "claim"),
+            SchedulerMode::Leader => write!(f, "leader"),
+        }
+    }
+}
+
+/// HTTP protocol used by the shared callback client
+#[derive(Debug, Clone, PartialEq)]
+pub enum HttpVersion {
+    /// HTTP/2 when negotiated through TLS ALPN, HTTP/1.1 otherwise
+    Auto,
+    /// Always HTTP/1.1
+    Http1,
+    /// Always HTTP/2 (prior knowledge, also over plain http://)
+    Http2,
+}
+
+impl std::fmt::Display for HttpVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HttpVersion::Auto => write!(f, "auto"),
+            HttpVersion::Http1 => write!(f, "http1"),
+            HttpVersion::Http2 => write!(f, "http2"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HttpClientConfig {
+    /// Idle connections kept per host
+    pub pool_max_idle_per_host: usize,
+    /// How long an idle connection stays in the pool
+    pub pool_idle_timeout_secs: u64,
+    /// TCP keep-alive interval (0 = disabled)
+    pub tcp_keepalive_secs: u64,
+    pub connect_timeout_secs: u64,
+    pub http_version: HttpVersion,
+    /// Proxy for all callback requests (HTTP_PROXY / HTTPS_PROXY are honored otherwise)
+    pub proxy_url: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -19,6 +104,50 @@ pub struct NatsConfig {
     pub password: Option<String>,
 }
 
+#[derive(Debug, Clone)]
+pub struct KafkaConfig {
+    /// Comma-separated bootstrap servers (host:port)
+    pub brokers: String,
+    pub client_id: String,
+    pub user: Option<String>,
+    pub password: Option<String>,
+    /// How long the producer retries a record before reporting failure
+    pub message_timeout_ms: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct RedisConfig {
+    pub host: String,
+    pub port: u16,
+    pub user: Option<String>,
+    pub password: Option<String>,
+    /// Logical database number
+    pub db: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct AmqpConfig {
+    pub host: String,
+    pub port: u16,
+    pub user: Option<String>,
+    pub password: Option<String>,
+    pub vhost: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct GrpcConfig {
+    /// Paths of protobuf descriptor sets (protoc --include_imports --descriptor_set_out)
+    pub descriptor_sets: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PostgresCallbackConfig {
+    /// Outbox tables Postgres callbacks may insert into
+    pub tables: Vec<String>,
+    /// Channels Postgres callbacks may pg_notify on
+    pub channels: Vec<String>,
+}
+
 impl Config {
     /// Load configuration from environment variables
     pub fn from_env() -> Result<Self> {
@@ -49,15 +178,133 @@ impl Config {
         // Load optional RUST_LOG with default "info"
         let rust_log = env::var("RUST_LOG").unwrap_or_else(|_| "info".to_string());
 
+        // Load optional INSTANCE_ID, falling back to the container hostname
+        let instance_id = env::var("INSTANCE_ID")
+            .or_else(|_| env::var("HOSTNAME"))
+            .ok()
+            .filter(|s| !s.trim().is_empty())
+            .unwrap_or_else(|| format!("timer-{}", &uuid::Uuid::new_v4().simple().to_string()[..8]));
+
+        // Load optional lease settings (default 300s lease, 5 attempts)
+        let lease_duration_secs = env::var("LEASE_DURATION_SECS")
+            .ok()
+            .and_then(|s| s.parse::<i64>().ok())
+            .unwrap_or(300);
+
+        if lease_duration_secs < 60 {
+            return Err(anyhow!(
+                "LEASE_DURATION_SECS must be at least 60 (got: {})",
+                lease_duration_secs
+            ));
+        }
+
+        let lease_max_attempts = env::var("LEASE_MAX_ATTEMPTS")
+            .ok()
+            .and_then(|s| s.parse::<i32>().ok())
+            .unwrap_or(5)
+            .max(1);
+
+        // Load optional SCHEDULER_MODE with default "claim"
+        let scheduler_mode = match env::var("SCHEDULER_MODE")
+            .unwrap_or_else(|_| "claim".to_string())
+            .to_lowercase()
+            .as_str()
+        {
+            "claim" => SchedulerMode::Claim,
+            "leader" => SchedulerMode::Leader,
+            other => {
+                return Err(anyhow!(
+                    "SCHEDULER_MODE must be 'claim' or 'leader' (got: {})",
+                    other
+                ));
+            }
+        };
+
+        // Load optional catch-up defaults (fire_all, 300s lateness for fire_within)
+        let default_catch_up_policy = env::var("CATCH_UP_POLICY")
+            .unwrap_or_else(|_| "fire_all".to_string())
+            .parse::<CatchUpPolicy>()
+            .map_err(|e| {
+                anyhow!(
+                    "CATCH_UP_POLICY must be fire_all, fire_within or mark_missed ({})",
+                    e
+                )
+            })?;
+
+        let default_max_lateness_secs = env::var("MAX_LATENESS_SECS")
+            .ok()
+            .and_then(|s| s.parse::<i32>().ok())
+            .unwrap_or(300)
+            .max(1);
+
+        // Load optional dispatch limits (256 callbacks, 32 per host)
+        let max_concurrent_callbacks = env::var("MAX_CONCURRENT_CALLBACKS")
+            .ok()
+            .and_then(|s| s.parse::<usize>().ok())
+            .unwrap_or(256)
+            .max(1);
+
+        let max_concurrent_per_host = env::var("MAX_CONCURRENT_PER_HOST")
+            .ok()
+            .and_then(|s| s.parse::<usize>().ok())
+            .unwrap_or(32)
+            .clamp(1, max_concurrent_callbacks);
+
+        // Load optional callback signing secrets (current and previous, for rotation)
+        let callback_signing_secrets = ["CALLBACK_SIGNING_SECRET", "CALLBACK_SIGNING_SECRET_PREVIOUS"]
+            .iter()
+            .filter_map(|name| env::var(name).ok())
+            .filter(|s| !s.trim().is_empty())
+            .collect::<Vec<_>>();
+
+        if callback_signing_secrets.iter().any(|s| s.len() < 16) {
+            return Err(anyhow!(
+                "CALLBACK_SIGNING_SECRET and CALLBACK_SIGNING_SECRET_PREVIOUS must be at least 16 characters long"
+            ));
+        }
+
+        // Build shared HTTP client config
+        let http_client = Self::build_http_client_config()?;
+
         // Build NATS config from components (optional)
         let nats_config = Self::build_nats_config()?;
 
+        // Build Kafka config from components (optional)
+        let kafka_config = Self::build_kafka_config()?;
+
+        // Build Redis config from components (optional)
+        let redis_config = Self::build_redis_config()?;
+
+        // Build AMQP config from components (optional)
+        let amqp_config = Self::build_amqp_config()?;
+
+        // Build gRPC config (optional)
+        let grpc_config = Self::build_grpc_config();
+
+        // Build Postgres callback targets (optional)
+        let postgres_callbacks = Self::build_postgres_callback_config()?;
+
         Ok(Config {
             database_url,
             api_key,
             port,
             rust_log,
+            instance_id,
+            lease_duration_secs,
+            lease_max_attempts,
+            scheduler_mode,
+            default_catch_up_policy,
+            default_max_lateness_secs,
+            max_concurrent_callbacks,
+            max_concurrent_per_host,
+            callback_signing_secrets,
+            http_client,
             nats_config,
+            kafka_config,
+            redis_config,
+            amqp_config,
+            grpc_config,
+            postgres_callbacks,
         })
     }
 
@@ -113,6 +360,62 @@ impl Config {
         Ok(url)
     }
 
+    /// Build shared HTTP client configuration from environment variables
+    ///
+    /// HTTP_POOL_MAX_IDLE_PER_HOST, HTTP_POOL_IDLE_TIMEOUT_SECS, HTTP_TCP_KEEPALIVE_SECS,
+    /// HTTP_CONNECT_TIMEOUT_SECS, HTTP_VERSION, HTTP_PROXY_URL (all optional)
+    fn build_http_client_config() -> Result<HttpClientConfig> {
+        let pool_max_idle_per_host = env::var("HTTP_POOL_MAX_IDLE_PER_HOST")
+            .ok()
+            .and_then(|s| s.parse::<usize>().ok())
+            .unwrap_or(32);
+
+        let pool_idle_timeout_secs = env::var("HTTP_POOL_IDLE_TIMEOUT_SECS")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(90);
+
+        let tcp_keepalive_secs = env::var("HTTP_TCP_KEEPALIVE_SECS")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(60);
+
+        let connect_timeout_secs = env::var("HTTP_CONNECT_TIMEOUT_SECS")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(10)
+            .max(1);
+
+        let http_version = match env::var("HTTP_VERSION")
+            .unwrap_or_else(|_| "auto".to_string())
+            .to_lowercase()
+            .as_str()
+        {
+            "auto" => HttpVersion::Auto,
+            "http1" => HttpVersion::Http1,
+            "http2" => HttpVersion::Http2,
+            other => {
+                return Err(anyhow!(
+                    "HTTP_VERSION must be 'auto', 'http1' or 'http2' (got: {})",
+                    other
+                ));
+            }
+        };
+
+        let proxy_url = env::var("HTTP_PROXY_URL")
+            .ok()
+            .filter(|s| !s.trim().is_empty());
+
+        Ok(HttpClientConfig {
+            pool_max_idle_per_host,
+            pool_idle_timeout_secs,
+            tcp_keepalive_secs,
+            connect_timeout_secs,
+            http_version,
+            proxy_url,
+        })
+    }
+
     /// Build NATS configuration from environment variables
     ///
     /// Uses component-based configuration:
@@ -150,4 +453,206 @@ impl Config {
             password,
         }))
     }
+
+    /// Build Kafka configuration from environment variables
+    ///
+    /// Uses component-based configuration:
+    /// KAFKA_BROKERS, KAFKA_CLIENT_ID, KAFKA_USER, KAFKA_PASSWORD, KAFKA_MESSAGE_TIMEOUT_MS
+    ///
+    /// Returns None if Kafka is not configured (KAFKA_BROKERS not set)
+    fn build_kafka_config() -> Result<Option<KafkaConfig>> {
+        // If no brokers are specified, Kafka is not configured
+        let brokers = match env::var("KAFKA_BROKERS") {
+            Ok(brokers) if !brokers.trim().is_empty() => brokers,
+            _ => return Ok(None),
+        };
+
+        let client_id = env::var("KAFKA_CLIENT_ID")
+            .ok()
+            .filter(|s| !s.trim().is_empty())
+            .unwrap_or_else(|| "timer-platform".to_string());
+
+        // Get user and password, treating empty strings as None
+        let user = env::var("KAFKA_USER")
+            .ok()
+            .and_then(|s| if s.trim().is_empty() { None } else { Some(s) });
+
+        let password = env::var("KAFKA_PASSWORD")
+            .ok()
+            .and_then(|s| if s.trim().is_empty() { None } else { Some(s) });
+
+        let message_timeout_ms = env::var("KAFKA_MESSAGE_TIMEOUT_MS")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(30000)
+            .max(1000);
+
+        Ok(Some(KafkaConfig {
+            brokers,
+            client_id,
+            user,
+            password,
+            message_timeout_ms,
+        }))
+    }
+
+    /// Build Redis configuration from environment variables
+    ///
+    /// Uses component-based configuration:
+    /// REDIS_HOST, REDIS_PORT, REDIS_USER, REDIS_PASSWORD, REDIS_DB
+    ///
+    /// Works the same against Valkey. Returns None if Redis is not configured
+    /// (REDIS_HOST not set)
+    fn build_redis_config() -> Result<Option<RedisConfig>> {
+        // Component-based configuration (optional)
+        let redis_host = env::var("REDIS_HOST").ok();
+
+        // If no host is specified, Redis is not configured
+        if redis_host.is_none() {
+            return Ok(None);
+        }
+
+        let host = redis_host.unwrap();
+        let port = env::var("REDIS_PORT")
+            .ok()
+            .and_then(|p| p.parse::<u16>().ok())
+            .unwrap_or(6379); // Default Redis port
+
+        // Get user and password, treating empty strings as None
+        let user = env::var("REDIS_USER")
+            .ok()
+            .and_then(|s| if s.trim().is_empty() { None } else { Some(s) });
+
+        let password = env::var("REDIS_PASSWORD")
+            .ok()
+            .and_then(|s| if s.trim().is_empty() { None } else { Some(s) });
+
+        let db = match env::var("REDIS_DB") {
+            Ok(db) => db
+                .parse::<i64>()
+                .ok()
+                .filter(|db| *db >= 0)
+                .ok_or_else(|| anyhow!("REDIS_DB must be a non-negative number (got: {})", db))?,
+            Err(_) => 0,
+        };
+
+        Ok(Some(RedisConfig {
+            host,
+            port,
+            user,
+            password,
+            db,
+        }))
+    }
+
+    /// Build AMQP configuration from environment variables
+    ///
+    /// Uses component-based configuration:
+    /// AMQP_HOST, AMQP_PORT, AMQP_USER, AMQP_PASSWORD, AMQP_VHOST
+    ///
+    /// Returns None if AMQP is not configured (AMQP_HOST not set)
+    fn build_amqp_config() -> Result<Option<AmqpConfig>> {
+        // Component-based configuration (optional)
+        let amqp_host = env::var("AMQP_HOST").ok();
+
+        // If no host is specified, AMQP is not configured
+        if amqp_host.is_none() {
+            return Ok(None);
+        }
+
+        let host = amqp_host.unwrap();
+        let port = env::var("AMQP_PORT")
+            .ok()
+            .and_then(|p| p.parse::<u16>().ok())
+            .unwrap_or(5672); // Default AMQP port
+
+        // Get user and password, treating empty strings as None
+        let user = env::var("AMQP_USER")
+            .ok()
+            .and_then(|s| if s.trim().is_empty() { None } else { Some(s) });
+
+        let password = env::var("AMQP_PASSWORD")
+            .ok()
+            .and_then(|s| if s.trim().is_empty() { None } else { Some(s) });
+
+        let vhost = env::var("AMQP_VHOST")
+            .ok()
+            .filter(|s| !s.is_empty())
+            .unwrap_or_else(|| "/".to_string());
+
+        Ok(Some(AmqpConfig {
+            host,
+            port,
+            user,
+            password,
+            vhost,
+        }))
+    }
+
+    /// Build gRPC configuration from environment variables
+    ///
+    /// GRPC_DESCRIPTOR_SETS is a comma-separated list of descriptor set files
+    /// whose services gRPC callbacks may call.
+    ///
+    /// Returns None if gRPC is not configured (GRPC_DESCRIPTOR_SETS not set)
+    fn build_grpc_config() -> Option<GrpcConfig> {
+        let descriptor_sets: Vec<String> = env::var("GRPC_DESCRIPTOR_SETS")
+            .ok()?
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        if descriptor_sets.is_empty() {
+            return None;
+        }
+
+        Some(GrpcConfig { descriptor_sets })
+    }
+
+    /// Build Postgres callback configuration from environment variables
+    ///
+    /// POSTGRES_CALLBACK_TABLES and POSTGRES_CALLBACK_CHANNELS are comma-separated
+    /// allowlists of outbox tables and notification channels.
+    ///
+    /// Returns None if neither is set (Postgres callbacks disabled)
+    fn build_postgres_callback_config() -> Result<Option<PostgresCallbackConfig>> {
+        let list = |name: &str| -> Vec<String> {
+            env::var(name)
+                .unwrap_or_default()
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect()
+        };
+
+        let tables = list("POSTGRES_CALLBACK_TABLES");
+        let channels = list("POSTGRES_CALLBACK_CHANNELS");
+
+        if tables.is_empty() && channels.is_empty() {
+            return Ok(None);
+        }
+
+        if let Some(table) = tables
+            .iter()
+            .find(|table| crate::callback_postgres::quote_table_name(table).is_none())
+        {
+            return Err(anyhow!(
+                "POSTGRES_CALLBACK_TABLES entries must be table or schema.table names (got: {})",
+                table
+            ));
+        }
+
+        if channels
+            .iter()
+            .any(|channel| channel == crate::db::TIMER_CHANGES_CHANNEL)
+        {
+            return Err(anyhow!(
+                "POSTGRES_CALLBACK_CHANNELS must not contain the internal channel {}",
+                crate::db::TIMER_CHANGES_CHANNEL
+            ));
+        }
+
+        Ok(Some(PostgresCallbackConfig { tables, channels }))
+    }
 }
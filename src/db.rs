@@ -1,31 +1,114 @@
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use serde_json::Value;
-use sqlx::{PgPool, Row};
+use sqlx::types::Json;
+use sqlx::{Acquire, PgConnection, PgExecutor, PgPool, Postgres, QueryBuilder, Row};
+use std::collections::HashMap;
 use uuid::Uuid;
 
-use crate::models::{CallbackConfig, CallbackType, Timer};
+use crate::models::{
+    CallbackConfig, CallbackType, CatchUpPolicy, RetryPolicy, Schedule, Timer, TimerChange,
+    TimerChangeOp, TimerExecution,
+};
+use crate::timer_queue::{LOAD_WINDOW_SECS, MAX_LOADED_TIMERS};
+
+/// Rows per INSERT statement in db_create_timers (12 parameters per row stays
+/// well below the 65535 bind parameters Postgres accepts per statement)
+const BATCH_INSERT_ROWS: usize = 1000;
+
+/// NOTIFY channel on which created, updated and canceled timers are announced
+pub const TIMER_CHANGES_CHANNEL: &str = "timer_changes";
+
+/// Announce a timer change to every listening scheduler
+///
+/// Run inside the transaction that made the change: Postgres delivers the
+/// notification on commit, and drops it on rollback.
+async fn notify_timer_change(conn: &mut PgConnection, op: TimerChangeOp, id: Uuid) -> Result<()> {
+    let payload = serde_json::to_string(&TimerChange { op, id })?;
+
+    sqlx::query("SELECT pg_notify($1, $2)")
+        .bind(TIMER_CHANGES_CHANNEL)
+        .bind(payload)
+        .execute(conn)
+        .await?;
+
+    Ok(())
+}
+
+/// Fields of a new timer for db_create_timer
+#[derive(Debug)]
+pub struct NewTimer {
+    pub execute_at: DateTime<Utc>,
+    pub callback_type: CallbackType,
+    pub callback_config: CallbackConfig,
+    pub metadata: Option<Value>,
+    pub retry_policy: Option<RetryPolicy>,
+    pub schedule: Option<Schedule>,
+    pub catch_up_policy: CatchUpPolicy,
+    pub max_lateness_secs: Option<i32>,
+    /// Client-supplied key deduplicating repeated creates
+    pub idempotency_key: Option<String>,
+    /// Fingerprint of the create request the key was sent with
+    pub idempotency_fingerprint: Option<String>,
+    /// Caller-chosen namespace and key identifying the timer (both or neither)
+    pub namespace: Option<String>,
+    pub external_key: Option<String>,
+}
+
+/// Outcome of db_create_timer
+#[derive(Debug)]
+pub enum CreatedTimer {
+    /// A new timer was inserted
+    New(Timer),
+    /// The idempotency key was already used for the same request; the timer created then
+    Existing(Timer),
+    /// The idempotency key was already used for a different request
+    KeyConflict,
+    /// Another timer already has the (namespace, external_key) pair
+    ExternalKeyTaken,
+}
 
 /// Create a new timer
-pub async fn db_create_timer(
-    pool: &PgPool,
-    execute_at: DateTime<Utc>,
-    callback_type: CallbackType,
-    callback_config: CallbackConfig,
-    metadata: Option<Value>,
-) -> Result<Timer> {
+///
+/// With an idempotency key, a timer already created with that key is
+/// returned instead of inserting a new one, provided it was created from the
+/// same request (same fingerprint).
+pub async fn db_create_timer(pool: &PgPool, new_timer: NewTimer) -> Result<CreatedTimer> {
+    let NewTimer {
+        execute_at,
+        callback_type,
+        callback_config,
+        metadata,
+        retry_policy,
+        schedule,
+        catch_up_policy,
+        max_lateness_secs,
+        idempotency_key,
+        idempotency_fingerprint,
+        namespace,
+        external_key,
+    } = new_timer;
+
     // Serialize callback_config to JSON
     let callback_config_json = serde_json::to_value(&callback_config)?;
 
-    let timer = sqlx::query_as::<_, Timer>(
+    let mut tx = pool.begin().await?;
+
+    let inserted = sqlx::query_as::<_, Timer>(
         r#"
         INSERT INTO timers (
-            id, execute_at, callback_type, callback_config, metadata, status
+            id, execute_at, callback_type, callback_config, metadata, status,
+            retry_policy, schedule, catch_up_policy, max_lateness_secs,
+            idempotency_key, idempotency_fingerprint, namespace, external_key, occurrence_at
         )
-        VALUES ($1, $2, $3, $4, $5, $6)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $2)
+        ON CONFLICT (idempotency_key) WHERE idempotency_key IS NOT NULL DO NOTHING
         RETURNING
             id, created_at, updated_at, execute_at, callback_type,
-            callback_config, status, last_error, executed_at, metadata
+            callback_config, status, last_error, executed_at, metadata,
+            attempts, retry_policy, schedule, occurrences, lease_expires_at,
+            claimed_by, catch_up_policy, max_lateness_secs, namespace, external_key,
+            occurrence_at
         "#,
     )
     .bind(Uuid::new_v4())
@@ -34,10 +117,184 @@ pub async fn db_create_timer(
     .bind(callback_config_json)
     .bind(metadata)
     .bind("pending")
-    .fetch_one(pool)
+    .bind(retry_policy.map(Json))
+    .bind(schedule.map(Json))
+    .bind(catch_up_policy)
+    .bind(max_lateness_secs)
+    .bind(&idempotency_key)
+    .bind(&idempotency_fingerprint)
+    .bind(namespace)
+    .bind(external_key)
+    .fetch_optional(&mut *tx)
+    .await;
+
+    let inserted = match inserted {
+        Err(sqlx::Error::Database(err))
+            if err.constraint() == Some("idx_timers_namespace_external_key") =>
+        {
+            return Ok(CreatedTimer::ExternalKeyTaken);
+        }
+        result => result?,
+    };
+
+    let Some(timer) = inserted else {
+        // Nothing inserted: the key is taken (a concurrent create with the
+        // same key has committed by the time ON CONFLICT returns)
+        let existing = sqlx::query_as::<_, Timer>(
+            r#"
+            SELECT
+                id, created_at, updated_at, execute_at, callback_type,
+                callback_config, status, last_error, executed_at, metadata,
+                attempts, retry_policy, schedule, occurrences, lease_expires_at,
+                claimed_by, catch_up_policy, max_lateness_secs, namespace, external_key,
+                occurrence_at
+            FROM timers
+            WHERE idempotency_key = $1 AND idempotency_fingerprint IS NOT DISTINCT FROM $2
+            "#,
+        )
+        .bind(&idempotency_key)
+        .bind(&idempotency_fingerprint)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        return Ok(match existing {
+            Some(timer) => CreatedTimer::Existing(timer),
+            None => CreatedTimer::KeyConflict,
+        });
+    };
+
+    notify_timer_change(&mut tx, TimerChangeOp::Created, timer.id).await?;
+    tx.commit().await?;
+
+    Ok(CreatedTimer::New(timer))
+}
+
+/// Look up the timer created earlier with an idempotency key
+///
+/// Returns Existing if it was created from the same request (same
+/// fingerprint), KeyConflict if from a different one, and None if the key is unused.
+pub async fn db_find_idempotent_create(
+    pool: &PgPool,
+    idempotency_key: &str,
+    idempotency_fingerprint: &str,
+) -> Result<Option<CreatedTimer>> {
+    let row = sqlx::query(
+        r#"
+        SELECT id, idempotency_fingerprint IS NOT DISTINCT FROM $2 AS same_request
+        FROM timers
+        WHERE idempotency_key = $1
+        "#,
+    )
+    .bind(idempotency_key)
+    .bind(idempotency_fingerprint)
+    .fetch_optional(pool)
     .await?;
 
-    Ok(timer)
+    let Some(row) = row else {
+        return Ok(None);
+    };
+    if !row.try_get::<bool, _>("same_request")? {
+        return Ok(Some(CreatedTimer::KeyConflict));
+    }
+
+    let timer = db_get_timer(pool, row.try_get("id")?).await?;
+    Ok(timer.map(CreatedTimer::Existing))
+}
+
+/// Outcome of db_create_timers
+#[derive(Debug)]
+pub enum CreatedTimers {
+    /// Per new timer, in input order: the inserted timer, or None if its external key is already used
+    Inserted(Vec<Option<Timer>>),
+    /// Nothing inserted (all_or_nothing): input indices of timers whose external key is already used
+    RolledBack(Vec<usize>),
+}
+
+/// Create many timers in one transaction (multi-row INSERTs)
+///
+/// Timers whose (namespace, external_key) is already used are skipped; with
+/// `all_or_nothing` the whole batch is rolled back instead. Idempotency keys
+/// are not supported here. Only near-term timers are announced on
+/// timer_changes; the memory loader picks up the others before they are due.
+pub async fn db_create_timers(
+    pool: &PgPool,
+    new_timers: Vec<NewTimer>,
+    all_or_nothing: bool,
+) -> Result<CreatedTimers> {
+    let ids: Vec<Uuid> = new_timers.iter().map(|_| Uuid::new_v4()).collect();
+    let mut rows = Vec::with_capacity(new_timers.len());
+    for (id, new_timer) in ids.iter().zip(new_timers) {
+        let callback_config_json = serde_json::to_value(&new_timer.callback_config)?;
+        rows.push((*id, new_timer, callback_config_json));
+    }
+
+    let mut tx = pool.begin().await?;
+    let mut inserted: HashMap<Uuid, Timer> = HashMap::with_capacity(ids.len());
+
+    for chunk in rows.chunks(BATCH_INSERT_ROWS) {
+        let mut query = QueryBuilder::<Postgres>::new(
+            r#"
+            INSERT INTO timers (
+                id, execute_at, callback_type, callback_config, metadata, status,
+                retry_policy, schedule, catch_up_policy, max_lateness_secs,
+                namespace, external_key, occurrence_at
+            )
+            "#,
+        );
+        query.push_values(chunk, |mut row, (id, new_timer, callback_config_json)| {
+            row.push_bind(*id)
+                .push_bind(new_timer.execute_at)
+                .push_bind(&new_timer.callback_type)
+                .push_bind(callback_config_json)
+                .push_bind(&new_timer.metadata)
+                .push_bind("pending")
+                .push_bind(new_timer.retry_policy.as_ref().map(Json))
+                .push_bind(new_timer.schedule.as_ref().map(Json))
+                .push_bind(new_timer.catch_up_policy)
+                .push_bind(new_timer.max_lateness_secs)
+                .push_bind(&new_timer.namespace)
+                .push_bind(&new_timer.external_key)
+                .push_bind(new_timer.execute_at);
+        });
+        query.push(
+            r#"
+            ON CONFLICT DO NOTHING
+            RETURNING
+                id, created_at, updated_at, execute_at, callback_type,
+                callback_config, status, last_error, executed_at, metadata,
+                attempts, retry_policy, schedule, occurrences, lease_expires_at,
+                claimed_by, catch_up_policy, max_lateness_secs, namespace, external_key,
+                occurrence_at
+            "#,
+        );
+
+        let timers = query.build_query_as::<Timer>().fetch_all(&mut *tx).await?;
+        inserted.extend(timers.into_iter().map(|timer| (timer.id, timer)));
+    }
+
+    // New ids cannot conflict, so a skipped row means its external key is taken
+    // (returning early drops the transaction, rolling back the inserted rows)
+    if all_or_nothing && inserted.len() < ids.len() {
+        let taken = ids
+            .iter()
+            .enumerate()
+            .filter(|(_, id)| !inserted.contains_key(id))
+            .map(|(index, _)| index)
+            .collect();
+        return Ok(CreatedTimers::RolledBack(taken));
+    }
+
+    let near_term = Utc::now() + chrono::Duration::seconds(LOAD_WINDOW_SECS);
+    for timer in inserted.values() {
+        if timer.execute_at <= near_term {
+            notify_timer_change(&mut tx, TimerChangeOp::Created, timer.id).await?;
+        }
+    }
+    tx.commit().await?;
+
+    Ok(CreatedTimers::Inserted(
+        ids.iter().map(|id| inserted.remove(id)).collect(),
+    ))
 }
 
 /// Get timer by ID
@@ -46,7 +303,10 @@ pub async fn db_get_timer(pool: &PgPool, timer_id: Uuid) -> Result<Option<Timer>
         r#"
         SELECT
             id, created_at, updated_at, execute_at, callback_type,
-            callback_config, status, last_error, executed_at, metadata
+            callback_config, status, last_error, executed_at, metadata,
+            attempts, retry_policy, schedule, occurrences, lease_expires_at,
+            claimed_by, catch_up_policy, max_lateness_secs, namespace, external_key,
+            occurrence_at
         FROM timers
         WHERE id = $1
         "#,
@@ -58,69 +318,98 @@ pub async fn db_get_timer(pool: &PgPool, timer_id: Uuid) -> Result<Option<Timer>
     Ok(timer)
 }
 
+/// Find the id of the timer with a (namespace, external_key) pair
+pub async fn db_find_timer_id_by_key(
+    pool: &PgPool,
+    namespace: &str,
+    external_key: &str,
+) -> Result<Option<Uuid>> {
+    let id = sqlx::query_scalar::<_, Uuid>(
+        "SELECT id FROM timers WHERE namespace = $1 AND external_key = $2",
+    )
+    .bind(namespace)
+    .bind(external_key)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(id)
+}
+
 /// List timers with filtering, sorting, and pagination
+///
+/// `sort_field` and `sort_order` must be validated by the caller; all filter
+/// values are bound parameters.
 pub async fn db_list_timers(
     pool: &PgPool,
-    status_filter: Option<String>,
+    filter: &TimerFilter,
     limit: i64,
     offset: i64,
     sort_field: &str,
     sort_order: &str,
 ) -> Result<(Vec<Timer>, i64)> {
-    // Build the WHERE clause
-    let where_clause = if let Some(status) = &status_filter {
-        format!("WHERE status = '{}'", status)
-    } else {
-        String::new()
-    };
-
-    // Build ORDER BY clause
-    let order_clause = format!("ORDER BY {} {}", sort_field, sort_order);
-
-    // Build full query
-    let query = format!(
+    let mut query = QueryBuilder::<Postgres>::new(
         r#"
         SELECT
             id, created_at, updated_at, execute_at, callback_type,
-            callback_config, status, last_error, executed_at, metadata
+            callback_config, status, last_error, executed_at, metadata,
+            attempts, retry_policy, schedule, occurrences, lease_expires_at,
+            claimed_by, catch_up_policy, max_lateness_secs, namespace, external_key,
+            occurrence_at
         FROM timers
-        {}
-        {}
-        LIMIT $1 OFFSET $2
+        WHERE TRUE
         "#,
-        where_clause, order_clause
     );
+    filter.push_conditions(&mut query);
+    query
+        .push(format!(" ORDER BY {} {} LIMIT ", sort_field, sort_order))
+        .push_bind(limit)
+        .push(" OFFSET ")
+        .push_bind(offset);
 
-    let timers = sqlx::query_as::<_, Timer>(&query)
-        .bind(limit)
-        .bind(offset)
-        .fetch_all(pool)
-        .await?;
+    let timers = query.build_query_as::<Timer>().fetch_all(pool).await?;
 
     // Get total count
-    let count_query = format!("SELECT COUNT(*) as count FROM timers {}", where_clause);
+    let mut count_query = QueryBuilder::<Postgres>::new("SELECT COUNT(*) FROM timers WHERE TRUE");
+    filter.push_conditions(&mut count_query);
 
-    let row = sqlx::query(&count_query).fetch_one(pool).await?;
-    let total: i64 = row.try_get("count")?;
+    let total: i64 = count_query.build_query_scalar().fetch_one(pool).await?;
 
     Ok((timers, total))
 }
 
+/// Fields to change in db_update_timer (None = leave unchanged)
+#[derive(Debug, Default)]
+pub struct TimerUpdate {
+    pub execute_at: Option<DateTime<Utc>>,
+    pub callback_type: Option<CallbackType>,
+    pub callback_config: Option<CallbackConfig>,
+    pub metadata: Option<Value>,
+    pub retry_policy: Option<RetryPolicy>,
+    pub schedule: Option<Schedule>,
+    pub catch_up_policy: Option<CatchUpPolicy>,
+    pub max_lateness_secs: Option<i32>,
+}
+
 /// Update timer fields
-pub async fn db_update_timer(
-    pool: &PgPool,
-    timer_id: Uuid,
-    execute_at: Option<DateTime<Utc>>,
-    callback_type: Option<CallbackType>,
-    callback_config: Option<CallbackConfig>,
-    metadata: Option<Value>,
-) -> Result<Timer> {
+pub async fn db_update_timer(pool: &PgPool, timer_id: Uuid, update: TimerUpdate) -> Result<Timer> {
+    let TimerUpdate {
+        execute_at,
+        callback_type,
+        callback_config,
+        metadata,
+        retry_policy,
+        schedule,
+        catch_up_policy,
+        max_lateness_secs,
+    } = update;
+
     // Build dynamic update query
     let mut updates: Vec<String> = vec!["updated_at = NOW()".to_string()];
     let mut param_index = 2; // $1 is timer_id
 
     if execute_at.is_some() {
-        updates.push(format!("execute_at = ${}", param_index));
+        // A new execute_at also moves the current occurrence
+        updates.push(format!("execute_at = ${0}, occurrence_at = ${0}", param_index));
         param_index += 1;
     }
     if callback_type.is_some() {
@@ -133,13 +422,32 @@ pub async fn db_update_timer(
     }
     if metadata.is_some() {
         updates.push(format!("metadata = ${}", param_index));
+        param_index += 1;
+    }
+    if retry_policy.is_some() {
+        updates.push(format!("retry_policy = ${}", param_index));
+        param_index += 1;
+    }
+    if schedule.is_some() {
+        updates.push(format!("schedule = ${}", param_index));
+        param_index += 1;
+    }
+    if catch_up_policy.is_some() {
+        updates.push(format!("catch_up_policy = ${}", param_index));
+        param_index += 1;
+    }
+    if max_lateness_secs.is_some() {
+        updates.push(format!("max_lateness_secs = ${}", param_index));
     }
 
     let query = format!(
         r#"UPDATE timers SET {} WHERE id = $1
         RETURNING
             id, created_at, updated_at, execute_at, callback_type,
-            callback_config, status, last_error, executed_at, metadata
+            callback_config, status, last_error, executed_at, metadata,
+            attempts, retry_policy, schedule, occurrences, lease_expires_at,
+            claimed_by, catch_up_policy, max_lateness_secs, namespace, external_key,
+            occurrence_at
         "#,
         updates.join(", ")
     );
@@ -160,13 +468,31 @@ pub async fn db_update_timer(
     if let Some(meta) = metadata {
         q = q.bind(meta);
     }
+    if let Some(policy) = retry_policy {
+        q = q.bind(Json(policy));
+    }
+    if let Some(sched) = schedule {
+        q = q.bind(Json(sched));
+    }
+    if let Some(policy) = catch_up_policy {
+        q = q.bind(policy);
+    }
+    if let Some(lateness) = max_lateness_secs {
+        q = q.bind(lateness);
+    }
+
+    let mut tx = pool.begin().await?;
+    let timer = q.fetch_one(&mut *tx).await?;
+    notify_timer_change(&mut tx, TimerChangeOp::Updated, timer_id).await?;
+    tx.commit().await?;
 
-    let timer = q.fetch_one(pool).await?;
     Ok(timer)
 }
 
 /// Cancel a timer (soft delete)
 pub async fn db_cancel_timer(pool: &PgPool, timer_id: Uuid) -> Result<Timer> {
+    let mut tx = pool.begin().await?;
+
     let timer = sqlx::query_as::<_, Timer>(
         r#"
         UPDATE timers
@@ -174,87 +500,572 @@ pub async fn db_cancel_timer(pool: &PgPool, timer_id: Uuid) -> Result<Timer> {
         WHERE id = $1
         RETURNING
             id, created_at, updated_at, execute_at, callback_type,
-            callback_config, status, last_error, executed_at, metadata
+            callback_config, status, last_error, executed_at, metadata,
+            attempts, retry_policy, schedule, occurrences, lease_expires_at,
+            claimed_by, catch_up_policy, max_lateness_secs, namespace, external_key,
+            occurrence_at
         "#,
     )
     .bind(timer_id)
     .bind("canceled")
-    .fetch_one(pool)
+    .fetch_one(&mut *tx)
     .await?;
 
+    notify_timer_change(&mut tx, TimerChangeOp::Canceled, timer_id).await?;
+    tx.commit().await?;
+
     Ok(timer)
 }
 
+/// Conditions selecting timers for listing and bulk operations (None or empty = not filtered)
+#[derive(Debug, Default)]
+pub struct TimerFilter {
+    /// Status is one of these
+    pub statuses: Vec<String>,
+    /// Callback type is one of these
+    pub callback_types: Vec<String>,
+    /// Metadata contains this JSON object (metadata @> ...)
+    pub metadata: Option<Value>,
+    /// Metadata "tags" array contains this tag
+    pub tag: Option<String>,
+    pub namespace: Option<String>,
+    pub external_key_prefix: Option<String>,
+    /// Host of the HTTP callback URL or gRPC target (case-insensitive)
+    pub callback_host: Option<String>,
+    pub execute_at_from: Option<DateTime<Utc>>,
+    pub execute_at_to: Option<DateTime<Utc>>,
+    pub created_at_from: Option<DateTime<Utc>>,
+    pub created_at_to: Option<DateTime<Utc>>,
+    /// HTTP callback URL starts with this prefix
+    pub url_prefix: Option<String>,
+    /// NATS or Kafka topic, Redis key or AMQP routing key starts with this prefix
+    pub topic_prefix: Option<String>,
+}
+
+impl TimerFilter {
+    /// Append the filter's conditions (each starting with AND) as bound parameters
+    fn push_conditions(&self, query: &mut QueryBuilder<'_, Postgres>) {
+        if !self.statuses.is_empty() {
+            query
+                .push(" AND status = ANY(")
+                .push_bind(self.statuses.clone())
+                .push(")");
+        }
+        if !self.callback_types.is_empty() {
+            query
+                .push(" AND callback_type = ANY(")
+                .push_bind(self.callback_types.clone())
+                .push(")");
+        }
+        if let Some(metadata) = &self.metadata {
+            query.push(" AND metadata @> ").push_bind(metadata.clone());
+        }
+        if let Some(tag) = &self.tag {
+            query
+                .push(" AND metadata @> jsonb_build_object('tags', jsonb_build_array(")
+                .push_bind(tag.clone())
+                .push("::text))");
+        }
+        if let Some(namespace) = &self.namespace {
+            query.push(" AND namespace = ").push_bind(namespace.clone());
+        }
+        if let Some(prefix) = &self.external_key_prefix {
+            query
+                .push(" AND external_key LIKE ")
+                .push_bind(like_prefix(prefix));
+        }
+        if let Some(host) = &self.callback_host {
+            // Host part of scheme://[userinfo@]host[:port]/...
+            query
+                .push(
+                    r#" AND lower(substring(
+                        CASE callback_type
+                            WHEN 'http' THEN callback_config->>'url'
+                            WHEN 'grpc' THEN callback_config->>'target'
+                        END
+                        FROM '^[A-Za-z][A-Za-z0-9+.-]*://(?:[^@/]*@)?(\[[^]]*\]|[^:/?#]+)'
+                    )) = lower("#,
+                )
+                .push_bind(host.clone())
+                .push(")");
+        }
+        if let Some(from) = self.execute_at_from {
+            query.push(" AND execute_at >= ").push_bind(from);
+        }
+        if let Some(to) = self.execute_at_to {
+            query.push(" AND execute_at < ").push_bind(to);
+        }
+        if let Some(from) = self.created_at_from {
+            query.push(" AND created_at >= ").push_bind(from);
+        }
+        if let Some(to) = self.created_at_to {
+            query.push(" AND created_at < ").push_bind(to);
+        }
+        if let Some(prefix) = &self.url_prefix {
+            query
+                .push(" AND callback_config->>'url' LIKE ")
+                .push_bind(like_prefix(prefix));
+        }
+        if let Some(prefix) = &self.topic_prefix {
+            // Same expression as idx_timers_callback_destination
+            query
+                .push(
+                    " AND COALESCE(callback_config->>'topic', callback_config->>'key', \
+                     callback_config->>'routing_key') LIKE ",
+                )
+                .push_bind(like_prefix(prefix));
+        }
+    }
+}
+
+/// LIKE pattern matching strings that start with `prefix`
+fn like_prefix(prefix: &str) -> String {
+    let escaped = prefix
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("{}%", escaped)
+}
+
+/// Cancel all pending timers matching a filter in one statement
+///
+/// With `dry_run` the matching timers are only counted. Returns the number of
+/// timers canceled (or that would be canceled).
+pub async fn db_cancel_timers(pool: &PgPool, filter: &TimerFilter, dry_run: bool) -> Result<u64> {
+    if dry_run {
+        let mut query =
+            QueryBuilder::<Postgres>::new("SELECT COUNT(*) FROM timers WHERE status = 'pending'");
+        filter.push_conditions(&mut query);
+
+        let count: i64 = query.build_query_scalar().fetch_one(pool).await?;
+        return Ok(count as u64);
+    }
+
+    let mut tx = pool.begin().await?;
+
+    let mut query = QueryBuilder::<Postgres>::new(
+        "UPDATE timers SET status = 'canceled', updated_at = NOW() WHERE status = 'pending'",
+    );
+    filter.push_conditions(&mut query);
+    query.push(" RETURNING id, execute_at");
+
+    let canceled: Vec<(Uuid, DateTime<Utc>)> =
+        query.build_query_as().fetch_all(&mut *tx).await?;
+
+    // Only near-term timers are in the schedulers' memory
+    let near_term = Utc::now() + chrono::Duration::seconds(LOAD_WINDOW_SECS);
+    for (id, execute_at) in &canceled {
+        if *execute_at <= near_term {
+            notify_timer_change(&mut tx, TimerChangeOp::Canceled, *id).await?;
+        }
+    }
+    tx.commit().await?;
+
+    Ok(canceled.len() as u64)
+}
+
 /// Load near-term timers for scheduler
-/// Loads timers from NOW() - 5 minutes to NOW() + 1 minute
+/// Loads pending timers due up to NOW() + 1 minute, including overdue ones
+/// (their catch-up policy is applied when they are claimed), earliest first and
+/// at most MAX_LOADED_TIMERS so a large overdue backlog is not loaded at once
 pub async fn db_load_near_term_timers(pool: &PgPool) -> Result<Vec<Timer>> {
     let timers = sqlx::query_as::<_, Timer>(
         r#"
         SELECT
             id, created_at, updated_at, execute_at, callback_type,
-            callback_config, status, last_error, executed_at, metadata
+            callback_config, status, last_error, executed_at, metadata,
+            attempts, retry_policy, schedule, occurrences, lease_expires_at,
+            claimed_by, catch_up_policy, max_lateness_secs, namespace, external_key,
+            occurrence_at
         FROM timers
         WHERE status = $1
-        AND execute_at > NOW() - INTERVAL '5 minutes'
         AND execute_at <= NOW() + INTERVAL '1 minute'
         ORDER BY execute_at ASC
+        LIMIT $2
         "#,
     )
     .bind("pending")
+    .bind(MAX_LOADED_TIMERS)
     .fetch_all(pool)
     .await?;
 
     Ok(timers)
 }
 
-/// Mark timer as executing
-pub async fn db_mark_executing(pool: &PgPool, timer_id: Uuid) -> Result<()> {
-    sqlx::query(
+/// Atomically claim due timers for execution
+///
+/// Only rows that are still pending and due at `now` are claimed; rows locked by
+/// another scheduler instance are skipped, so every timer is claimed by exactly
+/// one instance. `now` is the executor's clock (the one it popped the timers
+/// with), so clock skew against the database cannot leave due timers unclaimed.
+/// Claimed timers move to executing with a lease owned by `instance_id`.
+/// Returns the claimed rows as stored in the database.
+pub async fn db_claim_timers(
+    pool: &PgPool,
+    timer_ids: &[Uuid],
+    now: DateTime<Utc>,
+    instance_id: &str,
+    lease_secs: i64,
+) -> Result<Vec<Timer>> {
+    let timers = sqlx::query_as::<_, Timer>(
         r#"
         UPDATE timers
-        SET status = $2, updated_at = NOW()
-        WHERE id = $1
+        SET status = $2, claimed_by = $3,
+            lease_expires_at = NOW() + make_interval(secs => $4), updated_at = NOW()
+        WHERE id IN (
+            SELECT id FROM timers
+            WHERE id = ANY($1)
+            AND status = 'pending'
+            AND execute_at <= $5
+            FOR UPDATE SKIP LOCKED
+        )
+        RETURNING
+            id, created_at, updated_at, execute_at, callback_type,
+            callback_config, status, last_error, executed_at, metadata,
+            attempts, retry_policy, schedule, occurrences, lease_expires_at,
+            claimed_by, catch_up_policy, max_lateness_secs, namespace, external_key,
+            occurrence_at
         "#,
     )
-    .bind(timer_id)
+    .bind(timer_ids)
     .bind("executing")
+    .bind(instance_id)
+    .bind(lease_secs as f64)
+    .bind(now)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(timers)
+}
+
+/// Renew the execution lease of a claimed timer while its callback is running
+///
+/// Returns false if the timer is no longer executing under `instance_id`'s
+/// claim (canceled, or requeued after its lease expired).
+pub async fn db_renew_lease(
+    pool: &PgPool,
+    timer_id: Uuid,
+    instance_id: &str,
+    lease_secs: i64,
+) -> Result<bool> {
+    let result = sqlx::query(
+        r#"
+        UPDATE timers
+        SET lease_expires_at = NOW() + make_interval(secs => $3), updated_at = NOW()
+        WHERE id = $1 AND status = 'executing' AND claimed_by = $2
+        "#,
+    )
+    .bind(timer_id)
+    .bind(instance_id)
+    .bind(lease_secs as f64)
     .execute(pool)
     .await?;
 
-    Ok(())
+    Ok(result.rows_affected() > 0)
 }
 
 /// Mark timer as completed
-pub async fn db_mark_completed(pool: &PgPool, timer_id: Uuid) -> Result<()> {
-    sqlx::query(
+///
+/// Like every status write after a dispatch, only applies while the timer is
+/// still executing under `instance_id`'s claim (it may have been canceled or
+/// requeued meanwhile). Returns false if the claim was lost. Takes any executor
+/// so Postgres callbacks can complete the timer in their delivery transaction.
+pub async fn db_mark_completed(
+    executor: impl PgExecutor<'_>,
+    timer_id: Uuid,
+    instance_id: &str,
+) -> Result<bool> {
+    let result = sqlx::query(
         r#"
         UPDATE timers
-        SET status = $2, executed_at = NOW(), updated_at = NOW()
-        WHERE id = $1
+        SET status = $2, attempts = attempts + 1, occurrences = occurrences + 1,
+            lease_expires_at = NULL, executed_at = NOW(), updated_at = NOW()
+        WHERE id = $1 AND status = 'executing' AND claimed_by = $3
         "#,
     )
     .bind(timer_id)
     .bind("completed")
-    .execute(pool)
+    .bind(instance_id)
+    .execute(executor)
     .await?;
 
-    Ok(())
+    Ok(result.rows_affected() > 0)
 }
 
-/// Mark timer as failed with error message
-pub async fn db_mark_failed(pool: &PgPool, timer_id: Uuid, error_message: String) -> Result<()> {
-    sqlx::query(
+/// Mark timer as failed with error message (false if the claim was lost)
+pub async fn db_mark_failed(
+    pool: &PgPool,
+    timer_id: Uuid,
+    instance_id: &str,
+    error_message: String,
+) -> Result<bool> {
+    let result = sqlx::query(
         r#"
         UPDATE timers
-        SET status = $2, last_error = $3, executed_at = NOW(), updated_at = NOW()
-        WHERE id = $1
+        SET status = $2, last_error = $3, attempts = attempts + 1,
+            lease_expires_at = NULL, executed_at = NOW(), updated_at = NOW()
+        WHERE id = $1 AND status = 'executing' AND claimed_by = $4
         "#,
     )
     .bind(timer_id)
     .bind("failed")
     .bind(error_message)
+    .bind(instance_id)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Mark a claimed timer as missed (too late to fire under its catch-up policy)
+///
+/// Returns false if the claim was lost.
+pub async fn db_mark_missed(
+    pool: &PgPool,
+    timer_id: Uuid,
+    instance_id: &str,
+    error_message: String,
+) -> Result<bool> {
+    let result = sqlx::query(
+        r#"
+        UPDATE timers
+        SET status = $2, last_error = $3, lease_expires_at = NULL, updated_at = NOW()
+        WHERE id = $1 AND status = 'executing' AND claimed_by = $4
+        "#,
+    )
+    .bind(timer_id)
+    .bind("missed")
+    .bind(error_message)
+    .bind(instance_id)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Return a failed timer to pending for another attempt (false if the claim was lost)
+///
+/// Announces the change so the retry is queued without waiting for the next reload.
+pub async fn db_schedule_retry(
+    pool: &PgPool,
+    timer_id: Uuid,
+    instance_id: &str,
+    retry_at: DateTime<Utc>,
+    error_message: String,
+) -> Result<bool> {
+    let mut tx = pool.begin().await?;
+
+    let result = sqlx::query(
+        r#"
+        UPDATE timers
+        SET status = $2, execute_at = $3, last_error = $4, attempts = attempts + 1,
+            lease_expires_at = NULL, updated_at = NOW()
+        WHERE id = $1 AND status = 'executing' AND claimed_by = $5
+        "#,
+    )
+    .bind(timer_id)
+    .bind("pending")
+    .bind(retry_at)
+    .bind(error_message)
+    .bind(instance_id)
+    .execute(&mut *tx)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Ok(false);
+    }
+
+    notify_timer_change(&mut tx, TimerChangeOp::Updated, timer_id).await?;
+    tx.commit().await?;
+
+    Ok(true)
+}
+
+/// Reschedule a recurring timer to its next occurrence
+///
+/// Resets the attempt counter for the new occurrence. `error_message` is set when
+/// the finished occurrence failed after exhausting its retries. Returns false if
+/// `instance_id` lost its claim (e.g. the timer was canceled mid-run), so a
+/// canceled timer is not brought back to pending. Announces the change so the
+/// next occurrence is queued without waiting for the next reload. Takes a pool
+/// or a connection so Postgres callbacks can reschedule in their delivery
+/// transaction.
+pub async fn db_schedule_next_occurrence<'c>(
+    conn: impl Acquire<'c, Database = Postgres>,
+    timer_id: Uuid,
+    instance_id: &str,
+    next_execute_at: DateTime<Utc>,
+    error_message: Option<String>,
+) -> Result<bool> {
+    let mut tx = conn.begin().await?;
+
+    let result = sqlx::query(
+        r#"
+        UPDATE timers
+        SET status = $2, execute_at = $3, occurrence_at = $3,
+            last_error = COALESCE($4, last_error),
+            attempts = 0, occurrences = occurrences + 1,
+            lease_expires_at = NULL, executed_at = NOW(), updated_at = NOW()
+        WHERE id = $1 AND status = 'executing' AND claimed_by = $5
+        "#,
+    )
+    .bind(timer_id)
+    .bind("pending")
+    .bind(next_execute_at)
+    .bind(error_message)
+    .bind(instance_id)
+    .execute(&mut *tx)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Ok(false);
+    }
+
+    notify_timer_change(&mut tx, TimerChangeOp::Updated, timer_id).await?;
+    tx.commit().await?;
+
+    Ok(true)
+}
+
+/// Return executing timers with an expired lease to pending
+///
+/// The lost dispatch counts as an attempt. Only timers that stay below
+/// `max_attempts` (or their retry policy's max_attempts, whichever is larger)
+/// are requeued; see db_take_exhausted_leases for the rest.
+/// Returns the number of requeued timers.
+pub async fn db_requeue_expired_leases(pool: &PgPool, max_attempts: i32) -> Result<u64> {
+    let result = sqlx::query(
+        r#"
+        UPDATE timers
+        SET status = $1, attempts = attempts + 1, lease_expires_at = NULL,
+            last_error = 'execution lease expired (scheduler stopped before finishing)',
+            updated_at = NOW()
+        WHERE status = 'executing'
+        AND lease_expires_at < NOW()
+        AND attempts + 1 < GREATEST(COALESCE((retry_policy->>'max_attempts')::int, 1), $2)
+        "#,
+    )
+    .bind("pending")
+    .bind(max_attempts)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Take expired leases of timers that reached `max_attempts`
+///
+/// Claims the rows for `instance_id` with a renewed lease, so concurrent
+/// sweepers skip them while the caller finishes them as failed occurrences.
+pub async fn db_take_exhausted_leases(
+    pool: &PgPool,
+    instance_id: &str,
+    max_attempts: i32,
+    lease_secs: i64,
+) -> Result<Vec<Timer>> {
+    let timers = sqlx::query_as::<_, Timer>(
+        r#"
+        UPDATE timers
+        SET claimed_by = $3, lease_expires_at = NOW() + make_interval(secs => $2),
+            updated_at = NOW()
+        WHERE status = 'executing'
+        AND lease_expires_at < NOW()
+        AND attempts + 1 >= GREATEST(COALESCE((retry_policy->>'max_attempts')::int, 1), $1)
+        RETURNING
+            id, created_at, updated_at, execute_at, callback_type,
+            callback_config, status, last_error, executed_at, metadata,
+            attempts, retry_policy, schedule, occurrences, lease_expires_at,
+            claimed_by, catch_up_policy, max_lateness_secs, namespace, external_key,
+            occurrence_at
+        "#,
+    )
+    .bind(max_attempts)
+    .bind(lease_secs as f64)
+    .bind(instance_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(timers)
+}
+
+/// Record a callback dispatch in the execution history
+pub async fn db_record_execution(pool: &PgPool, execution: &TimerExecution) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO timer_executions (
+            id, timer_id, occurrence, attempt, scheduled_at, started_at, finished_at,
+            duration_ms, status, http_status, nats_ack, response_body, error, instance_id
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+        "#,
+    )
+    .bind(execution.id)
+    .bind(execution.timer_id)
+    .bind(execution.occurrence)
+    .bind(execution.attempt)
+    .bind(execution.scheduled_at)
+    .bind(execution.started_at)
+    .bind(execution.finished_at)
+    .bind(execution.duration_ms)
+    .bind(&execution.status)
+    .bind(execution.http_status)
+    .bind(execution.nats_ack)
+    .bind(&execution.response_body)
+    .bind(&execution.error)
+    .bind(&execution.instance_id)
     .execute(pool)
     .await?;
 
     Ok(())
 }
+
+/// List execution history of a timer (newest first) with pagination
+pub async fn db_list_executions(
+    pool: &PgPool,
+    timer_id: Uuid,
+    limit: i64,
+    offset: i64,
+) -> Result<(Vec<TimerExecution>, i64)> {
+    let executions = sqlx::query_as::<_, TimerExecution>(
+        r#"
+        SELECT
+            id, timer_id, occurrence, attempt, scheduled_at, started_at, finished_at,
+            duration_ms, status, http_status, nats_ack, response_body, error, instance_id
+        FROM timer_executions
+        WHERE timer_id = $1
+        ORDER BY finished_at DESC
+        LIMIT $2 OFFSET $3
+        "#,
+    )
+    .bind(timer_id)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await?;
+
+    let row = sqlx::query("SELECT COUNT(*) as count FROM timer_executions WHERE timer_id = $1")
+        .bind(timer_id)
+        .fetch_one(pool)
+        .await?;
+    let total: i64 = row.try_get("count")?;
+
+    Ok((executions, total))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn like_prefix_matches_prefix() {
+        assert_eq!(like_prefix("orders/"), "orders/%");
+        assert_eq!(like_prefix(""), "%");
+    }
+
+    #[test]
+    fn like_prefix_escapes_wildcards() {
+        assert_eq!(like_prefix("50%_off"), r"50\%\_off%");
+        assert_eq!(like_prefix(r"a\b"), r"a\\b%");
+        assert_eq!(like_prefix(r"\%"), r"\\\%%");
+    }
+}
@@ -0,0 +1,203 @@
+//! Callback dispatch limiter module
+//! Bounds concurrent callbacks globally and per destination host, and tracks saturation
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{Notify, OwnedSemaphorePermit, Semaphore};
+
+use crate::models::{CallbackConfig, Timer};
+
+/// Concurrency slots for callback dispatch
+///
+/// The executor takes a global slot, plus a slot of the destination host for
+/// HTTP and gRPC callbacks, before it claims a due timer. A saturated instance
+/// (or host) leaves the remaining timers pending in the database (where other
+/// replicas can claim them) instead of piling up tasks.
+pub struct DispatchLimiter {
+    max_concurrency: usize,
+    max_per_host: usize,
+    global: Arc<Semaphore>,
+    hosts: Mutex<HashMap<String, Arc<Semaphore>>>,
+    released: Notify,
+    /// Last time a due timer had to wait for a slot
+    saturated_at: Mutex<Option<DateTime<Utc>>>,
+    /// Due timers left in the queue because no global slot was free
+    waiting_global: AtomicUsize,
+    /// Due timers left in the queue because their host had no free slot
+    waiting_host: AtomicUsize,
+    delayed_dispatches: AtomicU64,
+    delay_ms_total: AtomicU64,
+    delay_ms_max: AtomicU64,
+}
+
+/// Slots held by one dispatch; released on drop
+pub struct DispatchPermit {
+    /// The timer waited for a global or host slot after it was due
+    pub delayed: bool,
+    limiter: Arc<DispatchLimiter>,
+    host: Option<(String, OwnedSemaphorePermit)>,
+    global: Option<OwnedSemaphorePermit>,
+}
+
+/// Dispatch metrics (reported by /healthz)
+#[derive(Debug, Clone, Serialize)]
+pub struct DispatchStats {
+    pub max_concurrency: usize,
+    pub max_per_host: usize,
+    /// Callbacks currently being dispatched
+    pub in_flight: usize,
+    /// Due timers waiting for a global or host slot
+    pub queue_depth: usize,
+    /// Dispatches that fired late because they waited for a slot
+    pub delayed_dispatches: u64,
+    /// Total and worst lateness of those dispatches
+    pub delay_ms_total: u64,
+    pub delay_ms_max: u64,
+}
+
+impl DispatchLimiter {
+    pub fn new(max_concurrency: usize, max_per_host: usize) -> Self {
+        Self {
+            max_concurrency,
+            max_per_host,
+            global: Arc::new(Semaphore::new(max_concurrency)),
+            hosts: Mutex::new(HashMap::new()),
+            released: Notify::new(),
+            saturated_at: Mutex::new(None),
+            waiting_global: AtomicUsize::new(0),
+            waiting_host: AtomicUsize::new(0),
+            delayed_dispatches: AtomicU64::new(0),
+            delay_ms_total: AtomicU64::new(0),
+            delay_ms_max: AtomicU64::new(0),
+        }
+    }
+
+    /// Number of free global slots
+    pub fn available(&self) -> usize {
+        self.global.available_permits()
+    }
+
+    /// Record that `waiting` due timers are held back for lack of global slots
+    pub fn mark_saturated(&self, now: DateTime<Utc>, waiting: usize) {
+        *self.saturated_at.lock().unwrap() = Some(now);
+        self.waiting_global.store(waiting, Ordering::Relaxed);
+    }
+
+    /// Clear the global backlog once every due timer got a slot
+    pub fn clear_backlog(&self) {
+        self.waiting_global.store(0, Ordering::Relaxed);
+    }
+
+    /// Wait until a dispatch finishes and frees its slots
+    pub async fn released(&self) {
+        self.released.notified().await;
+    }
+
+    /// Record that `waiting` due timers are held back because their host has no free slot
+    pub fn mark_host_saturated(&self, now: DateTime<Utc>, waiting: usize) {
+        if waiting > 0 {
+            *self.saturated_at.lock().unwrap() = Some(now);
+        }
+        self.waiting_host.store(waiting, Ordering::Relaxed);
+    }
+
+    /// Take a global slot and the destination host slot for a due timer, without waiting
+    ///
+    /// Returns None if either is full; the timer then stays queued. The returned
+    /// permit records whether the timer was delayed by saturation.
+    pub fn try_acquire(self: &Arc<Self>, timer: &Timer) -> Option<DispatchPermit> {
+        let global = self.global.clone().try_acquire_owned().ok()?;
+
+        let host = match destination_host(&timer.callback_config) {
+            Some(host) => {
+                let semaphore = self
+                    .hosts
+                    .lock()
+                    .unwrap()
+                    .entry(host.clone())
+                    .or_insert_with(|| Arc::new(Semaphore::new(self.max_per_host)))
+                    .clone();
+                // A new host's semaphore has free slots, so no idle entry is left behind
+                let permit = semaphore.try_acquire_owned().ok()?;
+                Some((host, permit))
+            }
+            None => None,
+        };
+
+        let delayed = self
+            .saturated_at
+            .lock()
+            .unwrap()
+            .is_some_and(|at| at >= timer.execute_at);
+
+        Some(DispatchPermit {
+            delayed,
+            limiter: self.clone(),
+            host,
+            global: Some(global),
+        })
+    }
+
+    /// Record how late a delayed dispatch fired
+    pub fn record_delay(&self, lateness: chrono::Duration) {
+        let ms = lateness.num_milliseconds().max(0) as u64;
+        self.delayed_dispatches.fetch_add(1, Ordering::Relaxed);
+        self.delay_ms_total.fetch_add(ms, Ordering::Relaxed);
+        self.delay_ms_max.fetch_max(ms, Ordering::Relaxed);
+    }
+
+    /// Snapshot of the dispatch metrics
+    pub fn stats(&self) -> DispatchStats {
+        DispatchStats {
+            max_concurrency: self.max_concurrency,
+            max_per_host: self.max_per_host,
+            in_flight: self.max_concurrency - self.global.available_permits(),
+            queue_depth: self.waiting_global.load(Ordering::Relaxed)
+                + self.waiting_host.load(Ordering::Relaxed),
+            delayed_dispatches: self.delayed_dispatches.load(Ordering::Relaxed),
+            delay_ms_total: self.delay_ms_total.load(Ordering::Relaxed),
+            delay_ms_max: self.delay_ms_max.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl Drop for DispatchPermit {
+    fn drop(&mut self) {
+        if let Some((host, permit)) = self.host.take() {
+            drop(permit);
+
+            // Forget idle hosts so the map does not grow with every destination seen
+            let mut hosts = self.limiter.hosts.lock().unwrap();
+            if let Some(semaphore) = hosts.get(&host) {
+                if Arc::strong_count(semaphore) == 1
+                    && semaphore.available_permits() == self.limiter.max_per_host
+                {
+                    hosts.remove(&host);
+                }
+            }
+        }
+        // Release the global slot before waking the executor
+        self.global.take();
+        self.limiter.released.notify_one();
+    }
+}
+
+/// Host a callback is sent to (None for callbacks without a per-host limit)
+fn destination_host(config: &CallbackConfig) -> Option<String> {
+    match config {
+        CallbackConfig::Http(http) => reqwest::Url::parse(&http.url)
+            .ok()
+            .and_then(|url| url.host_str().map(|host| host.to_lowercase())),
+        CallbackConfig::Grpc(grpc) => reqwest::Url::parse(&grpc.target)
+            .ok()
+            .and_then(|url| url.host_str().map(|host| host.to_lowercase())),
+        CallbackConfig::Nats(_)
+        | CallbackConfig::Kafka(_)
+        | CallbackConfig::Redis(_)
+        | CallbackConfig::Amqp(_)
+        | CallbackConfig::Postgres(_) => None,
+    }
+}
@@ -0,0 +1,117 @@
+//! Leader election module
+//! Runs the scheduler only on the replica holding a Postgres advisory lock
+
+use sqlx::{Connection, PgConnection, PgPool};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tokio::time::{sleep, Duration};
+
+use crate::callback::CallbackClients;
+use crate::config::Config;
+use crate::dispatch_limiter::DispatchLimiter;
+use crate::models::{SchedulerRole, TimerCache};
+use crate::scheduler::start_scheduler;
+
+/// Advisory lock key shared by all replicas ("timer" in ASCII)
+const LEADER_LOCK_KEY: i64 = 0x74_69_6d_65_72;
+
+/// How often followers retry the lock and the leader checks its connection
+const LEADER_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Start leader election in the background
+///
+/// The lock is taken with pg_try_advisory_lock on a dedicated connection (outside
+/// the pool), so it is held exactly as long as that session lives. When the
+/// leader's connection drops, Postgres releases the lock and a follower takes over.
+pub fn start_leader_election(
+    pool: PgPool,
+    cache: TimerCache,
+    clients: CallbackClients,
+    config: Config,
+    limiter: Arc<DispatchLimiter>,
+    role: Arc<RwLock<SchedulerRole>>,
+) {
+    tokio::spawn(async move {
+        loop {
+            let mut conn = match PgConnection::connect(&config.database_url).await {
+                Ok(conn) => conn,
+                Err(err) => {
+                    tracing::warn!("Leader election connection failed: {}", err);
+                    sleep(LEADER_CHECK_INTERVAL).await;
+                    continue;
+                }
+            };
+
+            // Follower: poll the lock until acquired or the connection fails
+            loop {
+                let acquired = sqlx::query_scalar::<_, bool>("SELECT pg_try_advisory_lock($1)")
+                    .bind(LEADER_LOCK_KEY)
+                    .fetch_one(&mut conn)
+                    .await;
+
+                match acquired {
+                    Ok(true) => {
+                        lead(
+                            &mut conn,
+                            &pool,
+                            &cache,
+                            &clients,
+                            &config,
+                            &limiter,
+                            &role,
+                        )
+                        .await;
+                        break;
+                    }
+                    Ok(false) => sleep(LEADER_CHECK_INTERVAL).await,
+                    Err(err) => {
+                        tracing::warn!("Failed to query leader lock: {}", err);
+                        break;
+                    }
+                }
+            }
+
+            *role.write().await = SchedulerRole::Follower;
+            sleep(LEADER_CHECK_INTERVAL).await;
+        }
+    });
+
+    tracing::info!("Leader election started (SCHEDULER_MODE=leader)");
+}
+
+/// Run the scheduler while the lock connection stays healthy
+async fn lead(
+    conn: &mut PgConnection,
+    pool: &PgPool,
+    cache: &TimerCache,
+    clients: &CallbackClients,
+    config: &Config,
+    limiter: &Arc<DispatchLimiter>,
+    role: &Arc<RwLock<SchedulerRole>>,
+) {
+    tracing::info!("Acquired scheduler leadership ({})", config.instance_id);
+    *role.write().await = SchedulerRole::Leader;
+
+    let handles = start_scheduler(
+        pool.clone(),
+        cache.clone(),
+        clients.clone(),
+        config.clone(),
+        limiter.clone(),
+    );
+
+    // Heartbeat: a failed query means the session (and the lock) is gone
+    loop {
+        sleep(LEADER_CHECK_INTERVAL).await;
+        if let Err(err) = sqlx::query("SELECT 1").execute(&mut *conn).await {
+            tracing::warn!("Lost scheduler leadership: {}", err);
+            break;
+        }
+    }
+
+    // Stop scheduling; in-flight callbacks finish on their own tasks
+    for handle in handles {
+        handle.abort();
+    }
+    cache.replace_all(Vec::new());
+}
@@ -1,32 +1,50 @@
+mod api_batch_create_timers;
 mod api_cancel_timer;
+mod api_cancel_timers;
 mod api_create_timer;
 mod api_get_timer;
 mod api_health;
+mod api_list_executions;
 mod api_list_timers;
+mod api_timer_by_key;
 mod api_update_timer;
 mod auth;
 mod callback;
+mod callback_amqp;
+mod callback_grpc;
 mod callback_http;
+mod callback_kafka;
 mod callback_nats;
+mod callback_postgres;
+mod callback_redis;
+mod change_listener;
 mod config;
 mod db;
+mod dispatch_limiter;
+mod leader;
 mod models;
+mod recurrence;
+mod retry;
 mod scheduler;
+mod signing;
+mod timer_queue;
 
 use axum::{
+    extract::DefaultBodyLimit,
     middleware,
     routing::{delete, get, post, put},
     Router,
 };
 use sqlx::postgres::PgPoolOptions;
-use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tower_http::trace::TraceLayer;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
-use crate::models::AppState;
+use crate::callback::CallbackClients;
+use crate::config::SchedulerMode;
+use crate::models::{AppState, SchedulerRole};
 
 #[tokio::main]
 async fn main() {
@@ -48,6 +66,17 @@ async fn main() {
     tracing::info!("Database URL: {}", mask_password(&config.database_url));
     tracing::info!("Server port: {}", config.port);
     tracing::info!("Log level: {}", config.rust_log);
+    tracing::info!("Instance ID: {}", config.instance_id);
+    tracing::info!("Scheduler mode: {}", config.scheduler_mode);
+    tracing::info!(
+        "Callback concurrency: {} (per host: {})",
+        config.max_concurrent_callbacks,
+        config.max_concurrent_per_host
+    );
+    tracing::info!(
+        "Callback signing secrets: {}",
+        config.callback_signing_secrets.len()
+    );
 
     // Step 3: Connect to database
     tracing::info!("Connecting to database...");
@@ -69,9 +98,15 @@ async fn main() {
     tracing::info!("Database migrations completed");
 
     // Step 5: Initialize in-memory cache
-    let timer_cache = Arc::new(RwLock::new(HashMap::new()));
+    let timer_cache = Arc::new(timer_queue::TimerQueue::new());
     tracing::info!("In-memory cache initialized");
 
+    // Dispatch slots outlive leader terms, so in-flight callbacks stay counted
+    let dispatch_limiter = Arc::new(dispatch_limiter::DispatchLimiter::new(
+        config.max_concurrent_callbacks,
+        config.max_concurrent_per_host,
+    ));
+
     // Step 6: Initialize NATS client (optional)
     let nats_client = if let Some(nats_config) = &config.nats_config {
         let nats_url = format!("nats://{}:{}", nats_config.host, nats_config.port);
@@ -101,24 +136,185 @@ async fn main() {
         None
     };
 
-    // Step 7: Start scheduler
-    scheduler::start_scheduler(pool.clone(), timer_cache.clone(), nats_client.clone());
+    // Step 7: Initialize Kafka producer (optional)
+    let kafka_producer = if let Some(kafka_config) = &config.kafka_config {
+        tracing::info!("Connecting to Kafka at {}", kafka_config.brokers);
+
+        match callback_kafka::build_kafka_producer(kafka_config) {
+            Ok(producer) => {
+                tracing::info!("Kafka producer created");
+                Some(producer)
+            }
+            Err(e) => {
+                tracing::error!("Failed to create Kafka producer: {}", e);
+                panic!("Kafka producer creation failed: {}", e);
+            }
+        }
+    } else {
+        tracing::info!("Kafka not configured, Kafka callbacks disabled");
+        None
+    };
+
+    // Step 8: Connect to Redis (optional)
+    let redis_connection = if let Some(redis_config) = &config.redis_config {
+        tracing::info!(
+            "Connecting to Redis at {}:{} (db {})",
+            redis_config.host,
+            redis_config.port,
+            redis_config.db
+        );
+
+        match callback_redis::connect_redis(redis_config).await {
+            Ok(connection) => {
+                tracing::info!("Redis connection established");
+                Some(connection)
+            }
+            Err(e) => {
+                tracing::error!("Failed to connect to Redis: {}", e);
+                panic!("Redis connection failed: {}", e);
+            }
+        }
+    } else {
+        tracing::info!("Redis not configured, Redis callbacks disabled");
+        None
+    };
+
+    // Step 9: Connect to AMQP broker (optional)
+    let amqp_publisher = if let Some(amqp_config) = &config.amqp_config {
+        tracing::info!(
+            "Connecting to AMQP broker at {}:{} (vhost {})",
+            amqp_config.host,
+            amqp_config.port,
+            amqp_config.vhost
+        );
 
-    // Step 8: Create shared AppState
+        match callback_amqp::AmqpPublisher::connect(amqp_config).await {
+            Ok(publisher) => {
+                tracing::info!("AMQP connection established");
+                Some(Arc::new(publisher))
+            }
+            Err(e) => {
+                tracing::error!("Failed to connect to AMQP broker: {}", e);
+                panic!("AMQP connection failed: {}", e);
+            }
+        }
+    } else {
+        tracing::info!("AMQP not configured, AMQP callbacks disabled");
+        None
+    };
+
+    // Step 10: Load gRPC descriptor sets (optional)
+    let grpc_client = if let Some(grpc_config) = &config.grpc_config {
+        tracing::info!(
+            "Loading gRPC descriptor sets: {}",
+            grpc_config.descriptor_sets.join(", ")
+        );
+
+        match callback_grpc::GrpcClient::load(grpc_config) {
+            Ok(client) => {
+                tracing::info!(
+                    "gRPC descriptors loaded ({} services)",
+                    client.service_count()
+                );
+                Some(Arc::new(client))
+            }
+            Err(e) => {
+                tracing::error!("Failed to load gRPC descriptor sets: {:#}", e);
+                panic!("gRPC descriptor loading failed: {:#}", e);
+            }
+        }
+    } else {
+        tracing::info!("gRPC not configured, gRPC callbacks disabled");
+        None
+    };
+
+    // Step 11: Build shared HTTP client (pooled across all HTTP callbacks)
+    let http_client = callback_http::build_http_client(&config.http_client)
+        .expect("Failed to build HTTP client");
+    tracing::info!(
+        "HTTP client initialized (version: {}, max idle per host: {}, proxy: {})",
+        config.http_client.http_version,
+        config.http_client.pool_max_idle_per_host,
+        config
+            .http_client
+            .proxy_url
+            .as_deref()
+            .map_or("none".to_string(), mask_password)
+    );
+
+    let callback_clients = CallbackClients {
+        http: http_client,
+        nats: nats_client,
+        kafka: kafka_producer,
+        redis: redis_connection,
+        amqp: amqp_publisher,
+        grpc: grpc_client,
+        postgres: config
+            .postgres_callbacks
+            .as_ref()
+            .map(callback_postgres::PostgresTargets::new),
+    };
+
+    // Step 12: Start scheduler (directly, or once elected leader)
+    let scheduler_role = match config.scheduler_mode {
+        SchedulerMode::Claim => {
+            scheduler::start_scheduler(
+                pool.clone(),
+                timer_cache.clone(),
+                callback_clients.clone(),
+                config.clone(),
+                dispatch_limiter.clone(),
+            );
+            Arc::new(RwLock::new(SchedulerRole::Active))
+        }
+        SchedulerMode::Leader => {
+            let role = Arc::new(RwLock::new(SchedulerRole::Follower));
+            leader::start_leader_election(
+                pool.clone(),
+                timer_cache.clone(),
+                callback_clients.clone(),
+                config.clone(),
+                dispatch_limiter.clone(),
+                role.clone(),
+            );
+            role
+        }
+    };
+
+    // Step 13: Create shared AppState
     let state = Arc::new(AppState {
         pool,
         config: config.clone(),
         timer_cache,
-        nats_client,
+        callback_clients,
+        scheduler_role,
+        dispatch_limiter,
     });
 
-    // Step 9: Build router with protected and public routes
+    // Step 14: Build router with protected and public routes
     let protected_routes = Router::new()
         .route("/timers", post(api_create_timer::create_timer))
         .route("/timers", get(api_list_timers::list_timers))
+        .route(
+            "/timers/batch",
+            post(api_batch_create_timers::batch_create_timers).layer(DefaultBodyLimit::max(
+                api_batch_create_timers::MAX_BATCH_BODY_BYTES,
+            )),
+        )
+        .route("/timers/cancel", post(api_cancel_timers::cancel_timers))
         .route("/timers/:id", get(api_get_timer::get_timer))
         .route("/timers/:id", put(api_update_timer::update_timer))
         .route("/timers/:id", delete(api_cancel_timer::cancel_timer))
+        .route(
+            "/timers/:id/executions",
+            get(api_list_executions::list_executions),
+        )
+        .route(
+            "/timers/by-key/:namespace/:key",
+            get(api_timer_by_key::get_timer_by_key)
+                .put(api_timer_by_key::update_timer_by_key)
+                .delete(api_timer_by_key::cancel_timer_by_key),
+        )
         .layer(middleware::from_fn_with_state(
             state.clone(),
             auth::auth_middleware,
@@ -130,7 +326,7 @@ async fn main() {
         .layer(TraceLayer::new_for_http())
         .with_state(state);
 
-    // Step 10: Start HTTP server
+    // Step 15: Start HTTP server
     let addr = SocketAddr::from(([0, 0, 0, 0], config.port));
     tracing::info!("Server listening on {}", addr);
 
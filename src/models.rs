@@ -1,13 +1,15 @@
-use async_nats::Client as NatsClient;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
 use sqlx::PgPool;
-use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use uuid::Uuid;
 
+use crate::callback::CallbackClients;
 use crate::config::Config;
+use crate::dispatch_limiter::DispatchLimiter;
+use crate::timer_queue::TimerQueue;
 
 // Timer status enum
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq)]
@@ -19,6 +21,17 @@ pub enum TimerStatus {
     Completed,
     Failed,
     Canceled,
+    Missed,
+}
+
+// Execution status enum (outcome of a single callback dispatch)
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq)]
+#[sqlx(type_name = "VARCHAR", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ExecutionStatus {
+    Completed,
+    Failed,
+    Missed,
 }
 
 // Callback type enum (discriminator for callback_config)
@@ -28,6 +41,78 @@ pub enum TimerStatus {
 pub enum CallbackType {
     Http,
     Nats,
+    Kafka,
+    Redis,
+    Amqp,
+    Grpc,
+    Postgres,
+}
+
+// How an overdue timer is handled when the scheduler finds it late
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq)]
+#[sqlx(type_name = "VARCHAR", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum CatchUpPolicy {
+    /// Always fire, however late
+    FireAll,
+    /// Fire if no later than max_lateness_secs, otherwise mark missed
+    FireWithin,
+    /// Never catch up: mark missed when later than MISSED_GRACE_SECS
+    MarkMissed,
+}
+
+/// Lateness tolerated by the mark_missed policy (covers normal scheduling delay)
+pub const MISSED_GRACE_SECS: i64 = 60;
+
+impl std::fmt::Display for CatchUpPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CatchUpPolicy::FireAll => write!(f, "fire_all"),
+            CatchUpPolicy::FireWithin => write!(f, "fire_within"),
+            CatchUpPolicy::MarkMissed => write!(f, "mark_missed"),
+        }
+    }
+}
+
+impl std::str::FromStr for CatchUpPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "fire_all" => Ok(CatchUpPolicy::FireAll),
+            "fire_within" => Ok(CatchUpPolicy::FireWithin),
+            "mark_missed" => Ok(CatchUpPolicy::MarkMissed),
+            _ => Err(format!("Invalid catch-up policy: {}", s)),
+        }
+    }
+}
+
+// Scheduler role of this instance (reported by /healthz)
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SchedulerRole {
+    /// Claim mode: this instance runs the scheduler alongside other replicas
+    Active,
+    /// Leader mode: this instance holds the advisory lock and runs the scheduler
+    Leader,
+    /// Leader mode: another instance is leader; only the REST API is served
+    Follower,
+}
+
+// Kind of change announced on the timer_changes NOTIFY channel
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TimerChangeOp {
+    Created,
+    Updated,
+    Canceled,
+}
+
+// Payload of a timer_changes notification (the row itself is re-read by listeners)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TimerChange {
+    pub op: TimerChangeOp,
+    pub id: Uuid,
 }
 
 impl std::fmt::Display for TimerStatus {
@@ -38,6 +123,7 @@ impl std::fmt::Display for TimerStatus {
             TimerStatus::Completed => write!(f, "completed"),
             TimerStatus::Failed => write!(f, "failed"),
             TimerStatus::Canceled => write!(f, "canceled"),
+            TimerStatus::Missed => write!(f, "missed"),
         }
     }
 }
@@ -52,29 +138,654 @@ impl std::str::FromStr for TimerStatus {
             "completed" => Ok(TimerStatus::Completed),
             "failed" => Ok(TimerStatus::Failed),
             "canceled" => Ok(TimerStatus::Canceled),
+            "missed" => Ok(TimerStatus::Missed),
             _ => Err(format!("Invalid timer status: {}", s)),
         }
     }
 }
 
+// HTTP method of an HTTP callback
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum HttpMethod {
+    Get,
+    #[default]
+    Post,
+    Put,
+    Patch,
+    Delete,
+}
+
+impl From<HttpMethod> for reqwest::Method {
+    fn from(method: HttpMethod) -> Self {
+        match method {
+            HttpMethod::Get => reqwest::Method::GET,
+            HttpMethod::Post => reqwest::Method::POST,
+            HttpMethod::Put => reqwest::Method::PUT,
+            HttpMethod::Patch => reqwest::Method::PATCH,
+            HttpMethod::Delete => reqwest::Method::DELETE,
+        }
+    }
+}
+
+/// Timeout of HTTP callbacks without timeout_secs
+pub const DEFAULT_HTTP_TIMEOUT_SECS: u64 = 30;
+
+/// Upper bound for timeout_secs (the execution lease is renewed while a callback runs)
+pub const MAX_HTTP_TIMEOUT_SECS: u64 = 300;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HTTPCallback {
     pub url: String,
+    /// Request method (default POST)
+    #[serde(default)]
+    pub method: HttpMethod,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub headers: Option<serde_json::Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub payload: Option<serde_json::Value>,
+    /// Request timeout in seconds (default DEFAULT_HTTP_TIMEOUT_SECS)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+    /// Status codes counted as success (default any 2xx)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub success_status_codes: Option<Vec<u16>>,
+    /// Status codes that fail the timer at once, skipping remaining retries (e.g. 410)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub permanent_failure_status_codes: Option<Vec<u16>>,
+    /// Per-timer signing secrets (current first), overriding CALLBACK_SIGNING_SECRET
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signing_secrets: Option<Vec<String>>,
+}
+
+/// Placeholder returned by the API instead of stored signing secrets
+pub const REDACTED_SECRET: &str = "********";
+
+impl HTTPCallback {
+    /// Validate URL, request options and signing secrets, returning a client-facing error message
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.url.starts_with("http://") && !self.url.starts_with("https://") {
+            return Err("HTTP callback URL must start with http:// or https://".to_string());
+        }
+        if self.method == HttpMethod::Get && self.payload.is_some() {
+            return Err("callback.payload is not allowed with method GET".to_string());
+        }
+        if let Some(timeout) = self.timeout_secs {
+            if !(1..=MAX_HTTP_TIMEOUT_SECS).contains(&timeout) {
+                return Err(format!(
+                    "callback.timeout_secs must be between 1 and {}",
+                    MAX_HTTP_TIMEOUT_SECS
+                ));
+            }
+        }
+        for (field, codes) in [
+            ("success_status_codes", &self.success_status_codes),
+            (
+                "permanent_failure_status_codes",
+                &self.permanent_failure_status_codes,
+            ),
+        ] {
+            if let Some(codes) = codes {
+                if codes.is_empty() || codes.iter().any(|code| !(100..=599).contains(code)) {
+                    return Err(format!(
+                        "callback.{} must contain status codes between 100 and 599",
+                        field
+                    ));
+                }
+            }
+        }
+        if let (Some(success), Some(permanent)) = (
+            &self.success_status_codes,
+            &self.permanent_failure_status_codes,
+        ) {
+            if success.iter().any(|code| permanent.contains(code)) {
+                return Err(
+                    "callback.success_status_codes and permanent_failure_status_codes must not overlap"
+                        .to_string(),
+                );
+            }
+        }
+        if let Some(secrets) = &self.signing_secrets {
+            if secrets.is_empty() || secrets.len() > crate::signing::MAX_SIGNING_SECRETS {
+                return Err(format!(
+                    "callback.signing_secrets must contain 1 to {} secrets",
+                    crate::signing::MAX_SIGNING_SECRETS
+                ));
+            }
+            if secrets.iter().any(|secret| secret.len() < 16) {
+                return Err(
+                    "callback.signing_secrets entries must be at least 16 characters".to_string(),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether a response status counts as success
+    pub fn is_success(&self, status: u16) -> bool {
+        match &self.success_status_codes {
+            Some(codes) => codes.contains(&status),
+            None => (200..300).contains(&status),
+        }
+    }
+
+    /// Whether a response status fails the timer without further retries
+    pub fn is_permanent_failure(&self, status: u16) -> bool {
+        self.permanent_failure_status_codes
+            .as_ref()
+            .is_some_and(|codes| codes.contains(&status))
+    }
+}
+
+// Delivery mode of a NATS callback
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum NatsMode {
+    /// Core NATS publish (fire-and-forget)
+    #[default]
+    Publish,
+    /// JetStream publish; the callback fails unless a stream acknowledges it
+    Jetstream,
+    /// Request/reply; the callback fails unless a consumer replies with a successful answer
+    Request,
+}
+
+/// How long a JetStream publish waits for its ack without ack_timeout_secs
+pub const DEFAULT_NATS_ACK_TIMEOUT_SECS: u64 = 5;
+
+/// Upper bound for ack_timeout_secs
+pub const MAX_NATS_ACK_TIMEOUT_SECS: u64 = 60;
+
+/// How long a NATS request waits for its reply without reply_timeout_secs
+pub const DEFAULT_NATS_REPLY_TIMEOUT_SECS: u64 = 30;
+
+/// Upper bound for reply_timeout_secs (the execution lease is renewed while a callback runs)
+pub const MAX_NATS_REPLY_TIMEOUT_SECS: u64 = 300;
+
+// Condition a NATS reply must meet for the callback to succeed
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ReplySuccess {
+    /// Any reply counts as success
+    #[default]
+    Any,
+    /// The reply body contains `value`
+    Contains { value: String },
+    /// The reply is JSON and the field at `path` (dot-separated) equals `equals`
+    JsonField {
+        path: String,
+        equals: serde_json::Value,
+    },
+}
+
+impl ReplySuccess {
+    /// Whether a reply body meets the condition
+    pub fn is_met(&self, reply: &[u8]) -> bool {
+        match self {
+            ReplySuccess::Any => true,
+            ReplySuccess::Contains { value } => {
+                String::from_utf8_lossy(reply).contains(value.as_str())
+            }
+            ReplySuccess::JsonField { path, equals } => {
+                let pointer = format!("/{}", path.replace('.', "/"));
+                serde_json::from_slice::<serde_json::Value>(reply)
+                    .ok()
+                    .and_then(|body| body.pointer(&pointer).cloned())
+                    .is_some_and(|value| value == *equals)
+            }
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NATSCallback {
     pub topic: String,
+    /// Delivery mode (default publish)
+    #[serde(default)]
+    pub mode: NatsMode,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub key: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub headers: Option<serde_json::Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub payload: Option<serde_json::Value>,
+    /// JetStream ack timeout in seconds (default DEFAULT_NATS_ACK_TIMEOUT_SECS)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ack_timeout_secs: Option<u64>,
+    /// Request mode: reply timeout in seconds (default DEFAULT_NATS_REPLY_TIMEOUT_SECS)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reply_timeout_secs: Option<u64>,
+    /// Request mode: condition the reply must meet (default any reply)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reply_success: Option<ReplySuccess>,
+}
+
+impl NATSCallback {
+    /// Validate topic and delivery options, returning a client-facing error message
+    pub fn validate(&self) -> Result<(), String> {
+        if self.topic.trim().is_empty() {
+            return Err("NATS topic cannot be empty".to_string());
+        }
+        if let Some(timeout) = self.ack_timeout_secs {
+            if self.mode != NatsMode::Jetstream {
+                return Err("callback.ack_timeout_secs requires mode jetstream".to_string());
+            }
+            if !(1..=MAX_NATS_ACK_TIMEOUT_SECS).contains(&timeout) {
+                return Err(format!(
+                    "callback.ack_timeout_secs must be between 1 and {}",
+                    MAX_NATS_ACK_TIMEOUT_SECS
+                ));
+            }
+        }
+        if let Some(timeout) = self.reply_timeout_secs {
+            if self.mode != NatsMode::Request {
+                return Err("callback.reply_timeout_secs requires mode request".to_string());
+            }
+            if !(1..=MAX_NATS_REPLY_TIMEOUT_SECS).contains(&timeout) {
+                return Err(format!(
+                    "callback.reply_timeout_secs must be between 1 and {}",
+                    MAX_NATS_REPLY_TIMEOUT_SECS
+                ));
+            }
+        }
+        if let Some(condition) = &self.reply_success {
+            if self.mode != NatsMode::Request {
+                return Err("callback.reply_success requires mode request".to_string());
+            }
+            match condition {
+                ReplySuccess::Contains { value } if value.is_empty() => {
+                    return Err("callback.reply_success.value cannot be empty".to_string());
+                }
+                ReplySuccess::JsonField { path, .. } if path.trim().is_empty() => {
+                    return Err("callback.reply_success.path cannot be empty".to_string());
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KafkaCallback {
+    pub topic: String,
+    /// Record key (also drives the default partitioner)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub headers: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payload: Option<serde_json::Value>,
+    /// Partition hint; the producer's partitioner picks one when omitted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub partition: Option<i32>,
+}
+
+impl KafkaCallback {
+    /// Validate topic and partition, returning a client-facing error message
+    pub fn validate(&self) -> Result<(), String> {
+        if self.topic.trim().is_empty() {
+            return Err("Kafka topic cannot be empty".to_string());
+        }
+        if matches!(self.partition, Some(partition) if partition < 0) {
+            return Err("callback.partition must be at least 0".to_string());
+        }
+        Ok(())
+    }
+}
+
+// Delivery mode of a Redis callback
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RedisMode {
+    /// XADD an entry to a stream
+    #[default]
+    Stream,
+    /// PUBLISH a message to a pub/sub channel
+    Publish,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedisCallback {
+    /// Stream key (stream mode) or channel name (publish mode)
+    pub key: String,
+    /// Delivery mode (default stream)
+    #[serde(default)]
+    pub mode: RedisMode,
+    /// Stream mode: extra string fields added to the entry next to "payload"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fields: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payload: Option<serde_json::Value>,
+    /// Stream mode: trim the stream to about this many entries (XADD MAXLEN ~)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub maxlen: Option<usize>,
+}
+
+impl RedisCallback {
+    /// Validate key and stream options, returning a client-facing error message
+    pub fn validate(&self) -> Result<(), String> {
+        if self.key.trim().is_empty() {
+            return Err("Redis key cannot be empty".to_string());
+        }
+        if let Some(fields) = &self.fields {
+            if self.mode != RedisMode::Stream {
+                return Err("callback.fields requires mode stream".to_string());
+            }
+            let Some(fields) = fields.as_object() else {
+                return Err("callback.fields must be an object".to_string());
+            };
+            if fields.contains_key("payload") {
+                return Err("callback.fields cannot contain \"payload\"".to_string());
+            }
+            if fields.values().any(|value| !value.is_string()) {
+                return Err("callback.fields values must be strings".to_string());
+            }
+        }
+        if let Some(maxlen) = self.maxlen {
+            if self.mode != RedisMode::Stream {
+                return Err("callback.maxlen requires mode stream".to_string());
+            }
+            if maxlen < 1 {
+                return Err("callback.maxlen must be at least 1".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// How long an AMQP publish waits for its confirm without confirm_timeout_secs
+pub const DEFAULT_AMQP_CONFIRM_TIMEOUT_SECS: u64 = 5;
+
+/// Upper bound for confirm_timeout_secs
+pub const MAX_AMQP_CONFIRM_TIMEOUT_SECS: u64 = 60;
+
+fn default_persistent() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AmqpCallback {
+    /// Exchange to publish to ("" is the default exchange, which routes by queue name)
+    #[serde(default)]
+    pub exchange: String,
+    #[serde(default)]
+    pub routing_key: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub headers: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payload: Option<serde_json::Value>,
+    /// Persistent delivery (delivery mode 2), so durable queues keep the message across broker restarts
+    #[serde(default = "default_persistent")]
+    pub persistent: bool,
+    /// Fail the callback when the message cannot be routed to any queue
+    #[serde(default)]
+    pub mandatory: bool,
+    /// Publisher confirm timeout in seconds (default DEFAULT_AMQP_CONFIRM_TIMEOUT_SECS)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confirm_timeout_secs: Option<u64>,
+}
+
+impl AmqpCallback {
+    /// Validate routing and delivery options, returning a client-facing error message
+    pub fn validate(&self) -> Result<(), String> {
+        if self.exchange.is_empty() && self.routing_key.trim().is_empty() {
+            return Err(
+                "callback.routing_key is required when publishing to the default exchange"
+                    .to_string(),
+            );
+        }
+        if let Some(headers) = &self.headers {
+            let Some(headers) = headers.as_object() else {
+                return Err("callback.headers must be an object".to_string());
+            };
+            if headers.values().any(|value| !value.is_string()) {
+                return Err("callback.headers values must be strings".to_string());
+            }
+        }
+        if let Some(timeout) = self.confirm_timeout_secs {
+            if !(1..=MAX_AMQP_CONFIRM_TIMEOUT_SECS).contains(&timeout) {
+                return Err(format!(
+                    "callback.confirm_timeout_secs must be between 1 and {}",
+                    MAX_AMQP_CONFIRM_TIMEOUT_SECS
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+// gRPC status code, by its canonical name (e.g. "NOT_FOUND")
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum GrpcCode {
+    Ok,
+    Cancelled,
+    Unknown,
+    InvalidArgument,
+    DeadlineExceeded,
+    NotFound,
+    AlreadyExists,
+    PermissionDenied,
+    ResourceExhausted,
+    FailedPrecondition,
+    Aborted,
+    OutOfRange,
+    Unimplemented,
+    Internal,
+    Unavailable,
+    DataLoss,
+    Unauthenticated,
+}
+
+impl From<tonic::Code> for GrpcCode {
+    fn from(code: tonic::Code) -> Self {
+        match code {
+            tonic::Code::Ok => GrpcCode::Ok,
+            tonic::Code::Cancelled => GrpcCode::Cancelled,
+            tonic::Code::Unknown => GrpcCode::Unknown,
+            tonic::Code::InvalidArgument => GrpcCode::InvalidArgument,
+            tonic::Code::DeadlineExceeded => GrpcCode::DeadlineExceeded,
+            tonic::Code::NotFound => GrpcCode::NotFound,
+            tonic::Code::AlreadyExists => GrpcCode::AlreadyExists,
+            tonic::Code::PermissionDenied => GrpcCode::PermissionDenied,
+            tonic::Code::ResourceExhausted => GrpcCode::ResourceExhausted,
+            tonic::Code::FailedPrecondition => GrpcCode::FailedPrecondition,
+            tonic::Code::Aborted => GrpcCode::Aborted,
+            tonic::Code::OutOfRange => GrpcCode::OutOfRange,
+            tonic::Code::Unimplemented => GrpcCode::Unimplemented,
+            tonic::Code::Internal => GrpcCode::Internal,
+            tonic::Code::Unavailable => GrpcCode::Unavailable,
+            tonic::Code::DataLoss => GrpcCode::DataLoss,
+            tonic::Code::Unauthenticated => GrpcCode::Unauthenticated,
+        }
+    }
+}
+
+impl std::fmt::Display for GrpcCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GrpcCode::Ok => write!(f, "OK"),
+            GrpcCode::Cancelled => write!(f, "CANCELLED"),
+            GrpcCode::Unknown => write!(f, "UNKNOWN"),
+            GrpcCode::InvalidArgument => write!(f, "INVALID_ARGUMENT"),
+            GrpcCode::DeadlineExceeded => write!(f, "DEADLINE_EXCEEDED"),
+            GrpcCode::NotFound => write!(f, "NOT_FOUND"),
+            GrpcCode::AlreadyExists => write!(f, "ALREADY_EXISTS"),
+            GrpcCode::PermissionDenied => write!(f, "PERMISSION_DENIED"),
+            GrpcCode::ResourceExhausted => write!(f, "RESOURCE_EXHAUSTED"),
+            GrpcCode::FailedPrecondition => write!(f, "FAILED_PRECONDITION"),
+            GrpcCode::Aborted => write!(f, "ABORTED"),
+            GrpcCode::OutOfRange => write!(f, "OUT_OF_RANGE"),
+            GrpcCode::Unimplemented => write!(f, "UNIMPLEMENTED"),
+            GrpcCode::Internal => write!(f, "INTERNAL"),
+            GrpcCode::Unavailable => write!(f, "UNAVAILABLE"),
+            GrpcCode::DataLoss => write!(f, "DATA_LOSS"),
+            GrpcCode::Unauthenticated => write!(f, "UNAUTHENTICATED"),
+        }
+    }
+}
+
+/// Deadline of gRPC callbacks without deadline_secs
+pub const DEFAULT_GRPC_DEADLINE_SECS: u64 = 30;
+
+/// Upper bound for deadline_secs (the execution lease is renewed while a callback runs)
+pub const MAX_GRPC_DEADLINE_SECS: u64 = 300;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GrpcCallback {
+    /// Server address, e.g. http://orders:50051 (https:// for TLS)
+    pub target: String,
+    /// Full method name (package.Service/Method) from a registered descriptor set
+    pub method: String,
+    /// Request metadata (ASCII values)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<serde_json::Value>,
+    /// Request message in the protobuf JSON mapping (empty message if omitted)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payload: Option<serde_json::Value>,
+    /// Call deadline in seconds (default DEFAULT_GRPC_DEADLINE_SECS)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deadline_secs: Option<u64>,
+    /// Status codes that count as success (default OK only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub success_codes: Option<Vec<GrpcCode>>,
+    /// Status codes that fail the timer immediately, skipping retries
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub permanent_failure_codes: Option<Vec<GrpcCode>>,
+}
+
+impl GrpcCallback {
+    /// Validate target, metadata and call options, returning a client-facing error message
+    ///
+    /// The method and payload are checked against the registered descriptors
+    /// separately (see callback_grpc::GrpcClient::check).
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.target.starts_with("http://") && !self.target.starts_with("https://") {
+            return Err("gRPC target must start with http:// or https://".to_string());
+        }
+        if self.method.trim().is_empty() {
+            return Err("gRPC method cannot be empty".to_string());
+        }
+        if let Some(metadata) = &self.metadata {
+            let Some(metadata) = metadata.as_object() else {
+                return Err("callback.metadata must be an object".to_string());
+            };
+            for (key, value) in metadata {
+                let valid = key.parse::<tonic::metadata::AsciiMetadataKey>().is_ok()
+                    && value
+                        .as_str()
+                        .is_some_and(|v| v.parse::<tonic::metadata::AsciiMetadataValue>().is_ok());
+                if !valid {
+                    return Err(format!(
+                        "callback.metadata entry '{}' must be an ASCII key with a string value",
+                        key
+                    ));
+                }
+            }
+        }
+        if let Some(deadline) = self.deadline_secs {
+            if !(1..=MAX_GRPC_DEADLINE_SECS).contains(&deadline) {
+                return Err(format!(
+                    "callback.deadline_secs must be between 1 and {}",
+                    MAX_GRPC_DEADLINE_SECS
+                ));
+            }
+        }
+        if matches!(&self.success_codes, Some(codes) if codes.is_empty()) {
+            return Err("callback.success_codes cannot be empty".to_string());
+        }
+        if let (Some(success), Some(permanent)) =
+            (&self.success_codes, &self.permanent_failure_codes)
+        {
+            if success.iter().any(|code| permanent.contains(code)) {
+                return Err(
+                    "callback.success_codes and permanent_failure_codes must not overlap"
+                        .to_string(),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether a call status counts as success
+    pub fn is_success(&self, code: GrpcCode) -> bool {
+        match &self.success_codes {
+            Some(codes) => codes.contains(&code),
+            None => code == GrpcCode::Ok,
+        }
+    }
+
+    /// Whether a call status fails the timer without further retries
+    pub fn is_permanent_failure(&self, code: GrpcCode) -> bool {
+        self.permanent_failure_codes
+            .as_ref()
+            .is_some_and(|codes| codes.contains(&code))
+    }
+}
+
+// Delivery mode of a Postgres callback
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum PostgresMode {
+    /// INSERT a row (timer_id, payload) into an outbox table
+    Outbox,
+    /// pg_notify on a channel
+    Notify,
+}
+
+/// Largest pg_notify payload Postgres accepts (bytes, exclusive)
+pub const MAX_PG_NOTIFY_PAYLOAD_BYTES: usize = 8000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostgresCallback {
+    pub mode: PostgresMode,
+    /// Outbox mode: table (optionally schema-qualified) from POSTGRES_CALLBACK_TABLES
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub table: Option<String>,
+    /// Notify mode: channel from POSTGRES_CALLBACK_CHANNELS
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payload: Option<serde_json::Value>,
+}
+
+impl PostgresCallback {
+    /// Validate mode-specific fields, returning a client-facing error message
+    ///
+    /// Whether the table or channel is allowed is checked separately
+    /// (see callback_postgres::PostgresTargets::check).
+    pub fn validate(&self) -> Result<(), String> {
+        match self.mode {
+            PostgresMode::Outbox => {
+                if self.table.as_deref().is_none_or(|t| t.trim().is_empty()) {
+                    return Err("callback.table is required with mode outbox".to_string());
+                }
+                if self.channel.is_some() {
+                    return Err("callback.channel requires mode notify".to_string());
+                }
+            }
+            PostgresMode::Notify => {
+                if self.channel.as_deref().is_none_or(|c| c.trim().is_empty()) {
+                    return Err("callback.channel is required with mode notify".to_string());
+                }
+                if self.table.is_some() {
+                    return Err("callback.table requires mode outbox".to_string());
+                }
+                let size = self
+                    .payload
+                    .as_ref()
+                    .map_or(0, |payload| payload.to_string().len());
+                if size >= MAX_PG_NOTIFY_PAYLOAD_BYTES {
+                    return Err(format!(
+                        "callback.payload must be smaller than {} bytes with mode notify",
+                        MAX_PG_NOTIFY_PAYLOAD_BYTES
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
 }
 
 // Callback configuration (internally-tagged enum for JSONB storage)
@@ -83,6 +794,145 @@ pub struct NATSCallback {
 pub enum CallbackConfig {
     Http(HTTPCallback),
     Nats(NATSCallback),
+    Kafka(KafkaCallback),
+    Redis(RedisCallback),
+    Amqp(AmqpCallback),
+    Grpc(GrpcCallback),
+    Postgres(PostgresCallback),
+}
+
+impl CallbackConfig {
+    /// Copy safe to return from the API (signing secrets masked)
+    pub fn redacted(&self) -> Self {
+        let mut config = self.clone();
+        if let CallbackConfig::Http(http) = &mut config {
+            if let Some(secrets) = &mut http.signing_secrets {
+                secrets.fill(REDACTED_SECRET.to_string());
+            }
+        }
+        config
+    }
+}
+
+/// Upper bound for retry_policy delays (30 days)
+pub const MAX_RETRY_DELAY_SECS: i64 = 30 * 24 * 60 * 60;
+
+// Retry policy for failed callbacks (exponential backoff)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first one
+    pub max_attempts: i32,
+    /// Delay before the first retry, in seconds
+    pub initial_delay_secs: i64,
+    /// Factor applied to the delay after every failed attempt
+    pub multiplier: f64,
+    /// Upper bound for the delay between two attempts, in seconds
+    pub max_delay_secs: i64,
+    /// Random jitter as a fraction of the delay (0.0 = none, 1.0 = full)
+    pub jitter: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_delay_secs: 10,
+            multiplier: 2.0,
+            max_delay_secs: 3600,
+            jitter: 0.0,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Validate policy bounds, returning a client-facing error message
+    pub fn validate(&self) -> Result<(), String> {
+        if !(1..=100).contains(&self.max_attempts) {
+            return Err("retry_policy.max_attempts must be between 1 and 100".to_string());
+        }
+        if !(1..=MAX_RETRY_DELAY_SECS).contains(&self.initial_delay_secs) {
+            return Err(format!(
+                "retry_policy.initial_delay_secs must be between 1 and {}",
+                MAX_RETRY_DELAY_SECS
+            ));
+        }
+        if !self.multiplier.is_finite() || self.multiplier < 1.0 {
+            return Err("retry_policy.multiplier must be at least 1.0".to_string());
+        }
+        if self.max_delay_secs < self.initial_delay_secs {
+            return Err(
+                "retry_policy.max_delay_secs must be greater than or equal to initial_delay_secs"
+                    .to_string(),
+            );
+        }
+        if self.max_delay_secs > MAX_RETRY_DELAY_SECS {
+            return Err(format!(
+                "retry_policy.max_delay_secs must be at most {}",
+                MAX_RETRY_DELAY_SECS
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.jitter) {
+            return Err("retry_policy.jitter must be between 0.0 and 1.0".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Upper bound for interval schedules' seconds (about 10 years)
+pub const MAX_SCHEDULE_INTERVAL_SECS: i64 = 10 * 365 * 24 * 60 * 60;
+
+// Recurrence rule (internally-tagged enum, stored inside the schedule JSONB)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ScheduleRule {
+    /// Cron expression (5 fields, or 6 with leading seconds), evaluated in UTC
+    Cron { expression: String },
+    /// Fixed interval between runs
+    Interval { seconds: i64 },
+}
+
+// Recurring schedule with optional end conditions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Schedule {
+    #[serde(flatten)]
+    pub rule: ScheduleRule,
+    /// No occurrence is scheduled after this time
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub until: Option<DateTime<Utc>>,
+    /// Stop after this many runs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_occurrences: Option<i32>,
+}
+
+impl Schedule {
+    /// Validate rule and end conditions, returning a client-facing error message
+    pub fn validate(&self) -> Result<(), String> {
+        match &self.rule {
+            ScheduleRule::Cron { expression } => {
+                crate::recurrence::parse_cron(expression)?;
+            }
+            ScheduleRule::Interval { seconds } => {
+                if !(1..=MAX_SCHEDULE_INTERVAL_SECS).contains(seconds) {
+                    return Err(format!(
+                        "schedule.seconds must be between 1 and {}",
+                        MAX_SCHEDULE_INTERVAL_SECS
+                    ));
+                }
+            }
+        }
+        if let Some(until) = self.until {
+            if until <= Utc::now() {
+                return Err("schedule.until must be in the future".to_string());
+            }
+        }
+        if let Some(max) = self.max_occurrences {
+            if max < 1 {
+                return Err("schedule.max_occurrences must be at least 1".to_string());
+            }
+        }
+        Ok(())
+    }
 }
 
 // Internal Timer struct (matches database schema)
@@ -99,6 +949,51 @@ pub struct Timer {
     pub last_error: Option<String>,
     pub executed_at: Option<DateTime<Utc>>,
     pub metadata: Option<serde_json::Value>,
+    /// Number of callback dispatches made so far
+    pub attempts: i32,
+    /// Optional retry policy (None = single attempt)
+    pub retry_policy: Option<Json<RetryPolicy>>,
+    /// Optional recurring schedule (None = one-shot timer)
+    pub schedule: Option<Json<Schedule>>,
+    /// Number of finished runs (occurrences) so far
+    pub occurrences: i32,
+    /// Expiry of the execution lease (set while status is executing)
+    pub lease_expires_at: Option<DateTime<Utc>>,
+    /// Scheduler instance that last claimed the timer for execution
+    pub claimed_by: Option<String>,
+    /// Handling of the timer when it is found overdue
+    pub catch_up_policy: CatchUpPolicy,
+    /// Allowed lateness for the fire_within policy
+    pub max_lateness_secs: Option<i32>,
+    /// Caller-chosen namespace of external_key
+    pub namespace: Option<String>,
+    /// Caller-chosen key, unique within namespace
+    pub external_key: Option<String>,
+    /// Scheduled time of the current occurrence (execute_at before retries moved it)
+    pub occurrence_at: DateTime<Utc>,
+}
+
+// Execution history record (one row per callback dispatch)
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct TimerExecution {
+    pub id: Uuid,
+    pub timer_id: Uuid,
+    /// 1-based run number (recurring timers have several)
+    pub occurrence: i32,
+    /// 1-based attempt number within the occurrence
+    pub attempt: i32,
+    pub scheduled_at: DateTime<Utc>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub duration_ms: i64,
+    pub status: ExecutionStatus,
+    pub http_status: Option<i32>,
+    pub nats_ack: Option<bool>,
+    /// Response body, truncated to a few KB
+    pub response_body: Option<String>,
+    pub error: Option<String>,
+    /// Scheduler instance that dispatched the callback
+    pub instance_id: Option<String>,
 }
 
 // Shared response type (used by multiple endpoints)
@@ -110,6 +1005,8 @@ pub struct TimerResponse {
     pub callback_type: String,
     pub status: String,
     pub executed_at: Option<DateTime<Utc>>,
+    pub namespace: Option<String>,
+    pub external_key: Option<String>,
 }
 
 // Generic API response wrapper
@@ -146,15 +1043,45 @@ pub struct AppState {
     pub config: Config,
     #[allow(dead_code)] // Used by scheduler in background tasks
     pub timer_cache: TimerCache,
-    /// Optional NATS client for pub/sub callbacks (None if NATS_URL not configured)
-    pub nats_client: Option<NatsClient>,
+    /// Shared callback clients (HTTP, optional NATS)
+    pub callback_clients: CallbackClients,
+    /// Current scheduler role (changes on leader failover)
+    pub scheduler_role: Arc<RwLock<SchedulerRole>>,
+    /// Callback dispatch slots (metrics reported by /healthz)
+    pub dispatch_limiter: Arc<DispatchLimiter>,
 }
 
 // Type alias for timer cache
-pub type TimerCache = Arc<RwLock<HashMap<Uuid, Timer>>>;
+pub type TimerCache = Arc<TimerQueue>;
 
 // Helper functions for type conversions
 impl Timer {
+    /// Id of the current delivery, for consumer-side deduplication: the timer
+    /// id for one-shot timers, plus the occurrence number for recurring timers
+    /// so every run is distinct
+    pub fn delivery_id(&self) -> String {
+        match self.schedule {
+            Some(_) => format!("{}-{}", self.id, self.occurrences + 1),
+            None => self.id.to_string(),
+        }
+    }
+
+    /// Lateness at `now` if the catch-up policy says the timer must not fire anymore
+    pub fn missed_by(&self, now: DateTime<Utc>) -> Option<chrono::Duration> {
+        let lateness = now - self.execute_at;
+        let allowed_secs = match self.catch_up_policy {
+            CatchUpPolicy::FireAll => return None,
+            CatchUpPolicy::FireWithin => self.max_lateness_secs? as i64,
+            CatchUpPolicy::MarkMissed => MISSED_GRACE_SECS,
+        };
+
+        if lateness > chrono::Duration::seconds(allowed_secs) {
+            Some(lateness)
+        } else {
+            None
+        }
+    }
+
     /// Convert Timer to TimerResponse (summary view)
     pub fn to_response(&self) -> TimerResponse {
         TimerResponse {
@@ -164,9 +1091,16 @@ impl Timer {
             callback_type: match self.callback_type {
                 CallbackType::Http => "http".to_string(),
                 CallbackType::Nats => "nats".to_string(),
+                CallbackType::Kafka => "kafka".to_string(),
+                CallbackType::Redis => "redis".to_string(),
+                CallbackType::Amqp => "amqp".to_string(),
+                CallbackType::Grpc => "grpc".to_string(),
+                CallbackType::Postgres => "postgres".to_string(),
             },
             status: self.status.to_string(),
             executed_at: self.executed_at,
+            namespace: self.namespace.clone(),
+            external_key: self.external_key.clone(),
         }
     }
 }
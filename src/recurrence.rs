@@ -0,0 +1,174 @@
+//! Recurrence module
+//! Computes occurrences of recurring timers from cron expressions or fixed intervals
+
+use crate::models::{Schedule, ScheduleRule};
+use chrono::{DateTime, Duration, Utc};
+use std::str::FromStr;
+
+/// Parse a cron expression
+///
+/// Accepts standard 5-field expressions (minute precision) as well as the
+/// 6/7-field form with leading seconds understood by the `cron` crate.
+pub fn parse_cron(expression: &str) -> Result<cron::Schedule, String> {
+    let normalized = if expression.split_whitespace().count() == 5 {
+        format!("0 {}", expression.trim())
+    } else {
+        expression.trim().to_string()
+    };
+
+    cron::Schedule::from_str(&normalized)
+        .map_err(|e| format!("invalid cron expression '{}': {}", expression, e))
+}
+
+/// First occurrence of a new schedule strictly after `after` (None if out of range)
+pub fn first_occurrence(schedule: &Schedule, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let first = match &schedule.rule {
+        ScheduleRule::Cron { expression } => parse_cron(expression).ok()?.after(&after).next()?,
+        ScheduleRule::Interval { seconds } => {
+            after.checked_add_signed(Duration::try_seconds(*seconds)?)?
+        }
+    };
+
+    within_until(schedule, first)
+}
+
+/// Next occurrence after a run scheduled at `previous`
+///
+/// `occurrences` is the number of runs finished so far, including the current one.
+/// Occurrences that were missed while the platform was down are skipped, so the
+/// returned time is always in the future. Returns None when the schedule has ended
+/// (or its next occurrence is out of range).
+pub fn next_occurrence(
+    schedule: &Schedule,
+    previous: DateTime<Utc>,
+    occurrences: i32,
+) -> Option<DateTime<Utc>> {
+    if let Some(max) = schedule.max_occurrences {
+        if occurrences >= max {
+            return None;
+        }
+    }
+
+    let now = Utc::now();
+    let next = match &schedule.rule {
+        ScheduleRule::Cron { expression } => {
+            let from = previous.max(now);
+            parse_cron(expression).ok()?.after(&from).next()?
+        }
+        ScheduleRule::Interval { seconds } => {
+            let interval = Duration::try_seconds(*seconds)?;
+            let mut next = previous.checked_add_signed(interval)?;
+            if next <= now {
+                // Skip intervals that elapsed while the timer was not running
+                let behind = (now - previous).num_seconds() / seconds + 1;
+                let skipped = Duration::try_seconds(behind.checked_mul(*seconds)?)?;
+                next = previous.checked_add_signed(skipped)?;
+            }
+            next
+        }
+    };
+
+    within_until(schedule, next)
+}
+
+/// Drop occurrences past the schedule's end time
+fn within_until(schedule: &Schedule, at: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    match schedule.until {
+        Some(until) if at > until => None,
+        _ => Some(at),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Timelike};
+
+    fn interval(seconds: i64, until: Option<DateTime<Utc>>, max: Option<i32>) -> Schedule {
+        Schedule {
+            rule: ScheduleRule::Interval { seconds },
+            until,
+            max_occurrences: max,
+        }
+    }
+
+    /// A run scheduled in the future, so no occurrence counts as missed
+    fn upcoming() -> DateTime<Utc> {
+        Utc::now().with_nanosecond(0).unwrap() + Duration::hours(1)
+    }
+
+    #[test]
+    fn interval_adds_seconds_to_previous_run() {
+        let previous = upcoming();
+        let next = next_occurrence(&interval(60, None, None), previous, 1);
+        assert_eq!(next, Some(previous + Duration::seconds(60)));
+    }
+
+    #[test]
+    fn max_occurrences_ends_after_last_run() {
+        let previous = upcoming();
+        let schedule = interval(60, None, Some(3));
+        assert_eq!(
+            next_occurrence(&schedule, previous, 2),
+            Some(previous + Duration::seconds(60))
+        );
+        assert_eq!(next_occurrence(&schedule, previous, 3), None);
+        assert_eq!(next_occurrence(&schedule, previous, 4), None);
+    }
+
+    #[test]
+    fn until_is_inclusive() {
+        let previous = upcoming();
+        let until = previous + Duration::seconds(60);
+        assert_eq!(
+            next_occurrence(&interval(60, Some(until), None), previous, 1),
+            Some(until)
+        );
+        assert_eq!(
+            next_occurrence(&interval(61, Some(until), None), previous, 1),
+            None
+        );
+    }
+
+    #[test]
+    fn interval_skips_missed_occurrences() {
+        let previous = Utc::now() - Duration::seconds(250);
+        let next = next_occurrence(&interval(100, None, None), previous, 1).unwrap();
+        assert_eq!(next, previous + Duration::seconds(300));
+    }
+
+    #[test]
+    fn cron_runs_at_next_matching_time() {
+        let day = (upcoming() + Duration::days(1)).date_naive();
+        let previous = Utc.from_utc_datetime(&day.and_hms_opt(9, 0, 0).unwrap());
+        let schedule = Schedule {
+            rule: ScheduleRule::Cron {
+                expression: "0 9 * * *".to_string(),
+            },
+            until: None,
+            max_occurrences: None,
+        };
+        assert_eq!(
+            next_occurrence(&schedule, previous, 1),
+            Some(previous + Duration::days(1))
+        );
+    }
+
+    #[test]
+    fn out_of_range_interval_has_no_occurrence() {
+        let previous = upcoming();
+        let schedule = interval(i64::MAX, None, None);
+        assert_eq!(next_occurrence(&schedule, previous, 1), None);
+        assert_eq!(first_occurrence(&schedule, previous), None);
+        assert!(schedule.validate().is_err());
+    }
+
+    #[test]
+    fn first_occurrence_respects_until() {
+        let after = upcoming();
+        assert_eq!(
+            first_occurrence(&interval(60, Some(after), None), after),
+            None
+        );
+    }
+}
@@ -0,0 +1,112 @@
+//! Retry scheduling module
+//! Computes exponential backoff for failed callbacks based on the timer's retry policy
+
+use crate::models::{RetryPolicy, Timer};
+use chrono::{DateTime, Duration, Utc};
+use rand::Rng;
+
+/// Compute when a failed timer should be attempted again
+///
+/// `attempts` is the number of dispatches made so far, including the one that just failed.
+/// Returns None when the timer has no retry policy, the policy is exhausted or
+/// the retry time would be out of range.
+pub fn next_retry_at(timer: &Timer, attempts: i32) -> Option<DateTime<Utc>> {
+    let policy = timer.retry_policy.as_ref()?;
+
+    if attempts >= policy.max_attempts {
+        return None;
+    }
+
+    Utc::now().checked_add_signed(backoff_delay(policy, attempts))
+}
+
+/// Delay before the next attempt: initial * multiplier^(attempts - 1), capped at max_delay
+///
+/// Jitter subtracts a random fraction (up to `jitter`) of the delay to spread out retries.
+fn backoff_delay(policy: &RetryPolicy, attempts: i32) -> Duration {
+    let exponent = (attempts - 1).max(0);
+    let delay_secs = (policy.initial_delay_secs as f64 * policy.multiplier.powi(exponent))
+        .min(policy.max_delay_secs as f64);
+
+    let delay_secs = if policy.jitter > 0.0 {
+        let factor = rand::thread_rng().gen_range(0.0..=policy.jitter);
+        delay_secs * (1.0 - factor)
+    } else {
+        delay_secs
+    };
+
+    // Never retry sooner than one second from now
+    Duration::milliseconds(((delay_secs * 1000.0) as i64).max(1000))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(jitter: f64) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 10,
+            initial_delay_secs: 10,
+            multiplier: 2.0,
+            max_delay_secs: 60,
+            jitter,
+        }
+    }
+
+    #[test]
+    fn delay_grows_exponentially() {
+        let policy = policy(0.0);
+        assert_eq!(backoff_delay(&policy, 1), Duration::seconds(10));
+        assert_eq!(backoff_delay(&policy, 2), Duration::seconds(20));
+        assert_eq!(backoff_delay(&policy, 3), Duration::seconds(40));
+    }
+
+    #[test]
+    fn delay_is_capped_at_max_delay() {
+        let policy = policy(0.0);
+        assert_eq!(backoff_delay(&policy, 4), Duration::seconds(60));
+        assert_eq!(backoff_delay(&policy, 100), Duration::seconds(60));
+    }
+
+    #[test]
+    fn jitter_only_shortens_the_delay() {
+        let policy = policy(0.5);
+        for _ in 0..1000 {
+            let delay = backoff_delay(&policy, 3);
+            assert!(delay >= Duration::seconds(20), "{:?}", delay);
+            assert!(delay <= Duration::seconds(40), "{:?}", delay);
+        }
+        for _ in 0..1000 {
+            assert!(backoff_delay(&policy, 100) <= Duration::seconds(60));
+        }
+    }
+
+    #[test]
+    fn delays_are_bounded() {
+        let too_long = RetryPolicy {
+            max_delay_secs: i64::MAX,
+            ..policy(0.0)
+        };
+        assert!(too_long.validate().is_err());
+
+        let too_long = RetryPolicy {
+            initial_delay_secs: i64::MAX,
+            max_delay_secs: i64::MAX,
+            ..policy(0.0)
+        };
+        assert!(too_long.validate().is_err());
+        assert!(policy(0.0).validate().is_ok());
+    }
+
+    #[test]
+    fn delay_is_at_least_one_second() {
+        let policy = RetryPolicy {
+            initial_delay_secs: 1,
+            jitter: 1.0,
+            ..policy(1.0)
+        };
+        for _ in 0..1000 {
+            assert!(backoff_delay(&policy, 1) >= Duration::seconds(1));
+        }
+    }
+}
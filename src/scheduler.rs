@@ -1,27 +1,69 @@
-use async_nats::Client as NatsClient;
 use chrono::Utc;
 use sqlx::PgPool;
-use tokio::time::{interval, Duration};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+use tokio::time::{interval, interval_at, sleep, Duration, Instant};
 
-use crate::callback::execute_callback;
-use crate::db::{db_load_near_term_timers, db_mark_executing};
-use crate::models::TimerCache;
+use crate::callback::{
+    execute_callback, finish_failed_occurrence, skip_missed_occurrence, CallbackClients,
+};
+use crate::change_listener::run_change_listener;
+use crate::config::Config;
+use crate::db::{
+    db_claim_timers, db_load_near_term_timers, db_renew_lease, db_requeue_expired_leases,
+    db_take_exhausted_leases,
+};
+use crate::dispatch_limiter::DispatchLimiter;
+use crate::models::{Timer, TimerCache};
+use crate::timer_queue::MAX_LOADED_TIMERS;
 
-/// Start the scheduler with two background tasks:
+/// Longest the executor sleeps without a queued deadline or a change
+const EXECUTOR_MAX_IDLE: Duration = Duration::from_secs(30);
+
+/// Pause after a slot frees up while saturated, so claims are batched
+const SATURATED_CLAIM_DELAY: Duration = Duration::from_millis(10);
+
+/// Lease renewals per lease duration while a callback is running
+const LEASE_RENEWALS_PER_LEASE: u64 = 3;
+
+/// Start the scheduler with four background tasks:
 /// - Memory Loader (runs every 30s)
-/// - Execution Task (runs every 1s)
-pub fn start_scheduler(pool: PgPool, cache: TimerCache, nats_client: Option<NatsClient>) {
+/// - Change Listener (applies LISTEN/NOTIFY timer changes as they happen)
+/// - Execution Task (sleeps until the next deadline or a queue change)
+/// - Lease Sweeper (runs every 30s)
+///
+/// Callbacks are dispatched within the limiter's global and per-host slots.
+/// Returns the task handles so the caller can stop the scheduler (leader mode).
+pub fn start_scheduler(
+    pool: PgPool,
+    cache: TimerCache,
+    clients: CallbackClients,
+    config: Config,
+    limiter: Arc<DispatchLimiter>,
+) -> Vec<JoinHandle<()>> {
     // Clone for memory loader task
     let pool_loader = pool.clone();
     let cache_loader = cache.clone();
 
+    // Spawn Change Listener Task
+    let listener = tokio::spawn(run_change_listener(pool.clone(), cache.clone()));
+
     // Clone for execution task
     let pool_executor = pool.clone();
     let cache_executor = cache.clone();
-    let nats_executor = nats_client.clone();
+    let clients_executor = clients.clone();
+    let signing_secrets = Arc::new(config.callback_signing_secrets.clone());
+    let instance_id = config.instance_id.clone();
+    let lease_secs = config.lease_duration_secs;
+
+    // Clone for lease sweeper task
+    let pool_sweeper = pool.clone();
+    let instance_sweeper = config.instance_id.clone();
+    let lease_max_attempts = config.lease_max_attempts;
 
     // Spawn Memory Loader Task (30s interval)
-    tokio::spawn(async move {
+    let loader = tokio::spawn(async move {
         let mut interval = interval(Duration::from_secs(30));
 
         loop {
@@ -31,16 +73,16 @@ pub fn start_scheduler(pool: PgPool, cache: TimerCache, nats_client: Option<Nats
                 Ok(timers) => {
                     let count = timers.len();
 
-                    // Acquire write lock and replace entire cache
-                    let mut cache_guard = cache_loader.write().await;
-                    cache_guard.clear();
+                    // Replace entire queue (wakes the executor)
+                    cache_loader.replace_all(timers);
 
-                    for timer in timers {
-                        cache_guard.insert(timer.id, timer);
-                    }
-
-                    // Lock released automatically when guard drops
                     tracing::info!("Loaded {} timers into cache", count);
+                    if count as i64 >= MAX_LOADED_TIMERS {
+                        tracing::warn!(
+                            "Timer queue capped at {} timers; later due timers load on the next reload",
+                            MAX_LOADED_TIMERS
+                        );
+                    }
                 }
                 Err(err) => {
                     tracing::warn!("Failed to load near-term timers: {}", err);
@@ -49,60 +91,242 @@ pub fn start_scheduler(pool: PgPool, cache: TimerCache, nats_client: Option<Nats
         }
     });
 
-    // Spawn Execution Task (1s interval)
-    tokio::spawn(async move {
-        let mut interval = interval(Duration::from_secs(1));
+    // Spawn Execution Task (wakes at the next deadline or when a dispatch slot frees up)
+    let executor = tokio::spawn(async move {
+        let mut saturated = false;
 
         loop {
-            interval.tick().await;
-
             let now = Utc::now();
+            let capacity = limiter.available();
 
-            // Acquire read lock to find due timers
-            let due_timers = {
-                let cache_guard = cache_executor.read().await;
-                cache_guard
-                    .values()
-                    .filter(|t| t.execute_at <= now)
-                    .cloned()
-                    .collect::<Vec<_>>()
-            };
-            // Read lock released here
+            if capacity == 0 {
+                // Saturated: leave due timers queued (and pending in the database)
+                let waiting = cache_executor.count_due(now);
+                if waiting > 0 {
+                    if !saturated {
+                        tracing::warn!(
+                            "Callback dispatch saturated ({} in flight), {} due timers waiting",
+                            limiter.stats().in_flight,
+                            waiting
+                        );
+                        saturated = true;
+                    }
+                    limiter.mark_saturated(now, waiting);
+                }
 
-            let count = due_timers.len();
-            if count > 0 {
-                tracing::info!("Executing {} due timers", count);
+                tokio::select! {
+                    _ = limiter.released() => sleep(SATURATED_CLAIM_DELAY).await,
+                    _ = sleep(EXECUTOR_MAX_IDLE) => {}
+                }
+                continue;
+            }
+
+            // Take dispatch slots before claiming: timers whose host is saturated stay
+            // queued (and pending in the database, where other replicas can claim them)
+            let mut due_timers = Vec::new();
+            let mut permits = HashMap::new();
+            let mut host_saturated = Vec::new();
+            while due_timers.len() < capacity {
+                let Some(timer) = cache_executor.pop_due(now, 1).pop() else {
+                    break;
+                };
+                match limiter.try_acquire(&timer) {
+                    Some(permit) => {
+                        permits.insert(timer.id, permit);
+                        due_timers.push(timer);
+                    }
+                    None => host_saturated.push(timer),
+                }
             }
+            let host_waiting = host_saturated.len();
+            limiter.mark_host_saturated(now, host_waiting);
+            cache_executor.restore(host_saturated);
 
-            for timer in due_timers {
+            if due_timers.is_empty() {
+                if saturated {
+                    tracing::info!("Callback dispatch backlog cleared");
+                    limiter.clear_backlog();
+                    saturated = false;
+                }
+
+                // Sleep until the earliest deadline (after the timers waiting for their
+                // host), until a host slot frees up, or until the queue changes
+                let next_deadline = if host_waiting > 0 {
+                    cache_executor.next_deadline_after(now)
+                } else {
+                    cache_executor.next_deadline()
+                };
+                let idle = next_deadline
+                    .and_then(|deadline| (deadline - now).to_std().ok())
+                    .map_or(EXECUTOR_MAX_IDLE, |wait| wait.min(EXECUTOR_MAX_IDLE));
+
+                tokio::select! {
+                    _ = sleep(idle) => {}
+                    _ = cache_executor.changed() => {}
+                    _ = limiter.released(), if host_waiting > 0 => {}
+                }
+                continue;
+            }
+
+            let due_ids = due_timers.iter().map(|t| t.id).collect::<Vec<_>>();
+
+            // Claim due timers in database (other instances may win some of them)
+            let claimed = match db_claim_timers(
+                &pool_executor,
+                &due_ids,
+                now,
+                &instance_id,
+                lease_secs,
+            )
+            .await
+            {
+                Ok(claimed) => claimed,
+                Err(err) => {
+                    tracing::warn!("Failed to claim {} due timers: {}", due_ids.len(), err);
+
+                    // Put them back and retry shortly
+                    for timer in &due_timers {
+                        cache_executor.apply(timer);
+                    }
+                    sleep(Duration::from_secs(1)).await;
+                    continue;
+                }
+            };
+
+            tracing::info!(
+                "Executing {} due timers ({} claimed elsewhere or changed)",
+                claimed.len(),
+                due_ids.len() - claimed.len()
+            );
+
+            for timer in claimed {
                 let timer_id = timer.id;
                 let pool_clone = pool_executor.clone();
-                let nats_clone = nats_executor.clone();
+                let clients_clone = clients_executor.clone();
+                let instance_clone = instance_id.clone();
+                let secrets_clone = signing_secrets.clone();
+                let Some(permit) = permits.remove(&timer_id) else {
+                    continue;
+                };
 
-                // Mark as executing in database
-                match db_mark_executing(&pool_executor, timer_id).await {
-                    Ok(_) => {
-                        // Remove from cache
-                        cache_executor.write().await.remove(&timer_id);
+                // Apply catch-up policy to overdue timers
+                if let Some(lateness) = timer.missed_by(now) {
+                    skip_missed_occurrence(&pool_executor, timer, &instance_id, lateness).await;
+                    continue;
+                }
 
-                        // Spawn async task to execute callback
-                        tokio::spawn(async move {
-                            tracing::info!("Spawned callback for timer {}", timer_id);
+                if permit.delayed {
+                    limiter.record_delay(Utc::now() - timer.execute_at);
+                }
 
-                            execute_callback(&pool_clone, timer, nats_clone.as_ref()).await;
-                        });
-                    }
-                    Err(err) => {
+                // Spawn async task to execute callback (slots are released when it finishes)
+                tokio::spawn(async move {
+                    tracing::info!("Spawned callback for timer {}", timer_id);
+
+                    execute_with_lease_renewal(
+                        &pool_clone,
+                        timer,
+                        &clients_clone,
+                        &secrets_clone,
+                        &instance_clone,
+                        lease_secs,
+                    )
+                    .await;
+
+                    drop(permit);
+                });
+            }
+        }
+    });
+
+    // Spawn Lease Sweeper Task (30s interval)
+    let sweeper = tokio::spawn(async move {
+        let mut interval = interval(Duration::from_secs(30));
+
+        loop {
+            interval.tick().await;
+
+            // Requeue timers whose executing instance disappeared
+            match db_requeue_expired_leases(&pool_sweeper, lease_max_attempts).await {
+                Ok(0) => {}
+                Ok(count) => {
+                    tracing::warn!("Returned {} timers with expired leases to pending", count);
+                }
+                Err(err) => {
+                    tracing::warn!("Failed to requeue expired leases: {}", err);
+                }
+            }
+
+            // Finish timers that ran out of attempts
+            match db_take_exhausted_leases(
+                &pool_sweeper,
+                &instance_sweeper,
+                lease_max_attempts,
+                lease_secs,
+            )
+            .await
+            {
+                Ok(timers) => {
+                    for timer in timers {
                         tracing::warn!(
-                            "Failed to mark timer {} as executing: {}",
-                            timer_id,
-                            err
+                            "Timer {} exceeded {} attempts with expired leases",
+                            timer.id,
+                            lease_max_attempts
+                        );
+                        let error_msg = format!(
+                            "execution lease expired after {} attempts",
+                            timer.attempts + 1
                         );
+                        finish_failed_occurrence(
+                            &pool_sweeper,
+                            &timer,
+                            &instance_sweeper,
+                            error_msg,
+                        )
+                        .await;
                     }
                 }
+                Err(err) => {
+                    tracing::warn!("Failed to take exhausted leases: {}", err);
+                }
             }
         }
     });
 
-    tracing::info!("Scheduler started with Memory Loader (30s) and Execution Task (1s)");
+    tracing::info!(
+        "Scheduler started with Memory Loader (30s), Change Listener, Execution Task (deadline-driven) and Lease Sweeper (30s)"
+    );
+
+    vec![loader, listener, executor, sweeper]
+}
+
+/// Run a claimed timer's callback, renewing its lease until the dispatch finishes
+///
+/// Callback timeouts can be as long as the lease itself; without renewal a slow
+/// callback would be requeued by the Lease Sweeper while still in flight.
+async fn execute_with_lease_renewal(
+    pool: &PgPool,
+    timer: Timer,
+    clients: &CallbackClients,
+    signing_secrets: &[String],
+    instance_id: &str,
+    lease_secs: i64,
+) {
+    let timer_id = timer.id;
+    let period = Duration::from_secs(lease_secs as u64 / LEASE_RENEWALS_PER_LEASE);
+    let mut renewals = interval_at(Instant::now() + period, period);
+
+    let dispatch = execute_callback(pool, timer, clients, signing_secrets, instance_id);
+    tokio::pin!(dispatch);
+
+    loop {
+        tokio::select! {
+            _ = &mut dispatch => return,
+            _ = renewals.tick() => {
+                if let Err(e) = db_renew_lease(pool, timer_id, instance_id, lease_secs).await {
+                    tracing::warn!("Failed to renew lease of timer {}: {}", timer_id, e);
+                }
+            }
+        }
+    }
 }
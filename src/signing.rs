@@ -0,0 +1,75 @@
+//! Callback signing module
+//! HMAC-SHA256 signatures that let webhook receivers verify requests came from the platform
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+/// Header carrying the Unix timestamp (seconds) covered by the signature
+pub const TIMESTAMP_HEADER: &str = "X-Timer-Timestamp";
+
+/// Header carrying one `v1=<hex>` signature per active secret, comma-separated
+pub const SIGNATURE_HEADER: &str = "X-Timer-Signature";
+
+/// Maximum number of secrets valid at once (current and previous, during rotation)
+pub const MAX_SIGNING_SECRETS: usize = 2;
+
+/// Build the X-Timer-Signature value for a request body
+///
+/// Each secret signs `"{timestamp}.{body}"`; receivers accept the request if
+/// any `v1` signature matches one of their secrets.
+pub fn signature_header(secrets: &[String], timestamp: i64, body: &[u8]) -> String {
+    secrets
+        .iter()
+        .map(|secret| format!("v1={}", sign(secret, timestamp, body)))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Hex-encoded HMAC-SHA256 of `"{timestamp}.{body}"`
+fn sign(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    // HMAC accepts keys of any length
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC key");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Computed with the Python verifier from the README:
+    // hmac.new(secret, b"1730131200." + body, hashlib.sha256).hexdigest()
+    const BODY: &[u8] = br#"{"timer_id":"550e8400-e29b-41d4-a716-446655440000"}"#;
+    const TIMESTAMP: i64 = 1730131200;
+    const CURRENT_SIGNATURE: &str =
+        "90085a8545270d7b3b8505433ce01cfed80e58758d0dbbea153933d6b5bcd65b";
+    const PREVIOUS_SIGNATURE: &str =
+        "40442fc9cfa9ea2cddcbea45dc67dbe679833787a6579836704a9a01338ddae0";
+
+    #[test]
+    fn signs_timestamp_and_body() {
+        let header = signature_header(&["whsec_current".to_string()], TIMESTAMP, BODY);
+        assert_eq!(header, format!("v1={}", CURRENT_SIGNATURE));
+    }
+
+    #[test]
+    fn signs_with_every_secret_during_rotation() {
+        let secrets = ["whsec_current".to_string(), "whsec_previous".to_string()];
+        let header = signature_header(&secrets, TIMESTAMP, BODY);
+        assert_eq!(
+            header,
+            format!("v1={},v1={}", CURRENT_SIGNATURE, PREVIOUS_SIGNATURE)
+        );
+    }
+
+    #[test]
+    fn signature_covers_the_timestamp() {
+        let secrets = ["whsec_current".to_string()];
+        assert_ne!(
+            signature_header(&secrets, TIMESTAMP + 1, BODY),
+            signature_header(&secrets, TIMESTAMP, BODY)
+        );
+    }
+}
@@ -0,0 +1,148 @@
+//! In-memory timer queue
+//! Min-heap of near-term timers ordered by execute_at, with wake-ups on change
+
+use chrono::{DateTime, Duration, Utc};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::sync::Mutex;
+use tokio::sync::Notify;
+use uuid::Uuid;
+
+use crate::models::{Timer, TimerStatus};
+
+/// How far ahead timers are kept in memory (matches the memory loader query)
+pub const LOAD_WINDOW_SECS: i64 = 60;
+
+/// Most timers loaded into memory at once (earliest first; a backlog of
+/// overdue timers is worked through over several reloads)
+pub const MAX_LOADED_TIMERS: i64 = 10_000;
+
+/// Near-term pending timers, ordered by deadline
+///
+/// The heap may hold stale entries for timers that were removed or moved;
+/// they are skipped lazily when they reach the top, so every operation is
+/// O(log n) and an idle executor does no work until the next deadline.
+#[derive(Default)]
+pub struct TimerQueue {
+    state: Mutex<QueueState>,
+    changed: Notify,
+}
+
+#[derive(Default)]
+struct QueueState {
+    timers: HashMap<Uuid, Timer>,
+    heap: BinaryHeap<Reverse<(DateTime<Utc>, Uuid)>>,
+}
+
+impl QueueState {
+    /// Drop heap entries that no longer match a queued timer
+    fn discard_stale(&mut self) {
+        while let Some(Reverse((execute_at, id))) = self.heap.peek() {
+            match self.timers.get(id) {
+                Some(timer) if timer.execute_at == *execute_at => break,
+                _ => {
+                    self.heap.pop();
+                }
+            }
+        }
+    }
+}
+
+impl TimerQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace the whole queue (memory loader)
+    pub fn replace_all(&self, timers: Vec<Timer>) {
+        {
+            let mut state = self.state.lock().unwrap();
+            state.heap = timers
+                .iter()
+                .map(|t| Reverse((t.execute_at, t.id)))
+                .collect();
+            state.timers = timers.into_iter().map(|t| (t.id, t)).collect();
+        }
+        self.changed.notify_one();
+    }
+
+    /// Apply a created or updated timer: keep it if pending and near-term, drop it otherwise
+    pub fn apply(&self, timer: &Timer) {
+        let near_term = timer.execute_at <= Utc::now() + Duration::seconds(LOAD_WINDOW_SECS);
+
+        if timer.status == TimerStatus::Pending && near_term {
+            let mut state = self.state.lock().unwrap();
+            state.heap.push(Reverse((timer.execute_at, timer.id)));
+            state.timers.insert(timer.id, timer.clone());
+        } else {
+            self.remove(timer.id);
+        }
+        self.changed.notify_one();
+    }
+
+    /// Remove a timer (e.g. canceled)
+    pub fn remove(&self, timer_id: Uuid) {
+        self.state.lock().unwrap().timers.remove(&timer_id);
+    }
+
+    /// Take up to `max` timers due at `now`, earliest first
+    pub fn pop_due(&self, now: DateTime<Utc>, max: usize) -> Vec<Timer> {
+        let mut state = self.state.lock().unwrap();
+        let mut due = Vec::new();
+
+        while due.len() < max {
+            state.discard_stale();
+            match state.heap.peek() {
+                Some(Reverse((execute_at, _))) if *execute_at <= now => {
+                    let Reverse((_, id)) = state.heap.pop().unwrap();
+                    if let Some(timer) = state.timers.remove(&id) {
+                        due.push(timer);
+                    }
+                }
+                _ => break,
+            }
+        }
+
+        due
+    }
+
+    /// Put back popped timers that cannot be dispatched yet, without waking the executor
+    ///
+    /// A newer version applied since they were popped is kept.
+    pub fn restore(&self, timers: Vec<Timer>) {
+        let mut state = self.state.lock().unwrap();
+        for timer in timers {
+            state.heap.push(Reverse((timer.execute_at, timer.id)));
+            state.timers.entry(timer.id).or_insert(timer);
+        }
+    }
+
+    /// Number of queued timers due at `now`
+    pub fn count_due(&self, now: DateTime<Utc>) -> usize {
+        let state = self.state.lock().unwrap();
+        state.timers.values().filter(|t| t.execute_at <= now).count()
+    }
+
+    /// Deadline of the earliest queued timer
+    pub fn next_deadline(&self) -> Option<DateTime<Utc>> {
+        let mut state = self.state.lock().unwrap();
+        state.discard_stale();
+        state.heap.peek().map(|Reverse((execute_at, _))| *execute_at)
+    }
+
+    /// Deadline of the earliest queued timer that is not yet due at `now`
+    pub fn next_deadline_after(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let state = self.state.lock().unwrap();
+        state
+            .timers
+            .values()
+            .map(|t| t.execute_at)
+            .filter(|execute_at| *execute_at > now)
+            .min()
+    }
+
+    /// Wait until the queue changes (a single waiter, the executor, is expected)
+    pub async fn changed(&self) {
+        self.changed.notified().await;
+    }
+}
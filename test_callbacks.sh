@@ -1,7 +1,7 @@
 #!/bin/bash
 
 # Timer Platform - Callback Test Script
-# Tests both HTTP and NATS callback functionality
+# Tests HTTP, NATS, Kafka, Redis, AMQP and Postgres callback functionality
 
 set -e
 
@@ -22,6 +22,10 @@ FUTURE_2MIN=$(date -u -v+2M +"%Y-%m-%dT%H:%M:%SZ" 2>/dev/null || date -u -d "+2
 
 echo "Test timer 1 (HTTP): $FUTURE_1MIN"
 echo "Test timer 2 (NATS): $FUTURE_2MIN"
+echo "Test timer 3 (Kafka): $FUTURE_2MIN"
+echo "Test timer 4 (Redis): $FUTURE_2MIN"
+echo "Test timer 5 (AMQP): $FUTURE_2MIN"
+echo "Test timer 6 (Postgres): $FUTURE_2MIN"
 echo ""
 
 # Test 1: Create HTTP callback timer
@@ -64,6 +68,94 @@ echo "NATS Timer Created: $NATS_TIMER_ID"
 echo $NATS_RESPONSE | jq .
 echo ""
 
+# Test 2b: Create Kafka callback timer
+echo "=== Test 2b: Create Kafka Callback Timer ==="
+KAFKA_RESPONSE=$(curl -s -X POST $BASE_URL/timers \
+  -H "X-API-Key: $API_KEY" \
+  -H "Content-Type: application/json" \
+  -d "{
+    \"execute_at\": \"$FUTURE_2MIN\",
+    \"callback\": {
+      \"type\": \"kafka\",
+      \"topic\": \"timers-test-execution\",
+      \"key\": \"test-key-123\",
+      \"headers\": {\"X-Test-Header\": \"kafka-test\"},
+      \"payload\": {\"event\": \"kafka_callback_test\", \"timestamp\": \"$FUTURE_2MIN\"}
+    }
+  }")
+
+KAFKA_TIMER_ID=$(echo $KAFKA_RESPONSE | jq -r '.data.id')
+echo "Kafka Timer Created: $KAFKA_TIMER_ID"
+echo $KAFKA_RESPONSE | jq .
+echo ""
+
+# Test 2c: Create Redis stream callback timer
+echo "=== Test 2c: Create Redis Callback Timer ==="
+REDIS_RESPONSE=$(curl -s -X POST $BASE_URL/timers \
+  -H "X-API-Key: $API_KEY" \
+  -H "Content-Type: application/json" \
+  -d "{
+    \"execute_at\": \"$FUTURE_2MIN\",
+    \"callback\": {
+      \"type\": \"redis\",
+      \"key\": \"timers-test-execution\",
+      \"mode\": \"stream\",
+      \"fields\": {\"source\": \"redis-test\"},
+      \"payload\": {\"event\": \"redis_callback_test\", \"timestamp\": \"$FUTURE_2MIN\"},
+      \"maxlen\": 1000
+    }
+  }")
+
+REDIS_TIMER_ID=$(echo $REDIS_RESPONSE | jq -r '.data.id')
+echo "Redis Timer Created: $REDIS_TIMER_ID"
+echo $REDIS_RESPONSE | jq .
+echo ""
+
+# Test 2d: Create AMQP callback timer (default exchange routes to the queue named by routing_key)
+echo "=== Test 2d: Create AMQP Callback Timer ==="
+docker exec timer-rabbitmq rabbitmqadmin declare queue name=timers-test-execution durable=true > /dev/null
+AMQP_RESPONSE=$(curl -s -X POST $BASE_URL/timers \
+  -H "X-API-Key: $API_KEY" \
+  -H "Content-Type: application/json" \
+  -d "{
+    \"execute_at\": \"$FUTURE_2MIN\",
+    \"callback\": {
+      \"type\": \"amqp\",
+      \"exchange\": \"\",
+      \"routing_key\": \"timers-test-execution\",
+      \"headers\": {\"X-Test-Header\": \"amqp-test\"},
+      \"payload\": {\"event\": \"amqp_callback_test\", \"timestamp\": \"$FUTURE_2MIN\"},
+      \"mandatory\": true
+    }
+  }")
+
+AMQP_TIMER_ID=$(echo $AMQP_RESPONSE | jq -r '.data.id')
+echo "AMQP Timer Created: $AMQP_TIMER_ID"
+echo $AMQP_RESPONSE | jq .
+echo ""
+
+# Test 2e: Create Postgres outbox callback timer (timer_outbox is in POSTGRES_CALLBACK_TABLES)
+echo "=== Test 2e: Create Postgres Callback Timer ==="
+docker exec timer-postgres psql -q -U timer -d timerdb -c \
+  "CREATE TABLE IF NOT EXISTS timer_outbox (id BIGSERIAL PRIMARY KEY, timer_id UUID NOT NULL, payload JSONB, created_at TIMESTAMPTZ NOT NULL DEFAULT NOW())"
+PG_RESPONSE=$(curl -s -X POST $BASE_URL/timers \
+  -H "X-API-Key: $API_KEY" \
+  -H "Content-Type: application/json" \
+  -d "{
+    \"execute_at\": \"$FUTURE_2MIN\",
+    \"callback\": {
+      \"type\": \"postgres\",
+      \"mode\": \"outbox\",
+      \"table\": \"timer_outbox\",
+      \"payload\": {\"event\": \"postgres_callback_test\", \"timestamp\": \"$FUTURE_2MIN\"}
+    }
+  }")
+
+PG_TIMER_ID=$(echo $PG_RESPONSE | jq -r '.data.id')
+echo "Postgres Timer Created: $PG_TIMER_ID"
+echo $PG_RESPONSE | jq .
+echo ""
+
 # Test 3: Get timer details
 echo "=== Test 3: Get Timer Details ==="
 echo "HTTP Timer Details:"
@@ -76,6 +168,26 @@ curl -s -X GET $BASE_URL/timers/$NATS_TIMER_ID \
   -H "X-API-Key: $API_KEY" | jq '.data | {id, callback_type: .callback.type, topic: .callback.topic, key: .callback.key, status}'
 echo ""
 
+echo "Kafka Timer Details:"
+curl -s -X GET $BASE_URL/timers/$KAFKA_TIMER_ID \
+  -H "X-API-Key: $API_KEY" | jq '.data | {id, callback_type: .callback.type, topic: .callback.topic, key: .callback.key, status}'
+echo ""
+
+echo "Redis Timer Details:"
+curl -s -X GET $BASE_URL/timers/$REDIS_TIMER_ID \
+  -H "X-API-Key: $API_KEY" | jq '.data | {id, callback_type: .callback.type, key: .callback.key, mode: .callback.mode, status}'
+echo ""
+
+echo "AMQP Timer Details:"
+curl -s -X GET $BASE_URL/timers/$AMQP_TIMER_ID \
+  -H "X-API-Key: $API_KEY" | jq '.data | {id, callback_type: .callback.type, exchange: .callback.exchange, routing_key: .callback.routing_key, status}'
+echo ""
+
+echo "Postgres Timer Details:"
+curl -s -X GET $BASE_URL/timers/$PG_TIMER_ID \
+  -H "X-API-Key: $API_KEY" | jq '.data | {id, callback_type: .callback.type, mode: .callback.mode, table: .callback.table, status}'
+echo ""
+
 # Test 4: List all timers
 echo "=== Test 4: List All Timers ==="
 curl -s -X GET "$BASE_URL/timers?limit=5" \
@@ -138,10 +250,80 @@ else
 fi
 echo ""
 
+# Check Kafka timer execution (due at the same time as the NATS timer)
+echo "=== Test 8: Verify Kafka Timer Execution ==="
+KAFKA_RESULT=$(curl -s -X GET $BASE_URL/timers/$KAFKA_TIMER_ID -H "X-API-Key: $API_KEY")
+KAFKA_STATUS=$(echo $KAFKA_RESULT | jq -r '.data.status')
+
+if [ "$KAFKA_STATUS" = "completed" ]; then
+  echo "✅ Kafka Timer Executed Successfully"
+  echo "   Status: $KAFKA_STATUS"
+  echo "   Delivery: $(curl -s $BASE_URL/timers/$KAFKA_TIMER_ID/executions -H "X-API-Key: $API_KEY" | jq -r '.data.executions[0].response_body')"
+  echo "   Consume with: docker exec timer-kafka /opt/kafka/bin/kafka-console-consumer.sh --bootstrap-server localhost:9092 --topic timers-test-execution --from-beginning --max-messages 1"
+else
+  echo "❌ Kafka Timer Execution Failed"
+  echo "   Status: $KAFKA_STATUS"
+  echo "   Error: $(echo $KAFKA_RESULT | jq -r '.data.last_error')"
+fi
+echo ""
+
+# Check Redis timer execution (due at the same time as the NATS timer)
+echo "=== Test 9: Verify Redis Timer Execution ==="
+REDIS_RESULT=$(curl -s -X GET $BASE_URL/timers/$REDIS_TIMER_ID -H "X-API-Key: $API_KEY")
+REDIS_STATUS=$(echo $REDIS_RESULT | jq -r '.data.status')
+
+if [ "$REDIS_STATUS" = "completed" ]; then
+  echo "✅ Redis Timer Executed Successfully"
+  echo "   Status: $REDIS_STATUS"
+  echo "   Entry: $(curl -s $BASE_URL/timers/$REDIS_TIMER_ID/executions -H "X-API-Key: $API_KEY" | jq -r '.data.executions[0].response_body')"
+  echo "   Read with: docker exec timer-valkey valkey-cli XRANGE timers-test-execution - +"
+else
+  echo "❌ Redis Timer Execution Failed"
+  echo "   Status: $REDIS_STATUS"
+  echo "   Error: $(echo $REDIS_RESULT | jq -r '.data.last_error')"
+fi
+echo ""
+
+# Check AMQP timer execution (due at the same time as the NATS timer)
+echo "=== Test 10: Verify AMQP Timer Execution ==="
+AMQP_RESULT=$(curl -s -X GET $BASE_URL/timers/$AMQP_TIMER_ID -H "X-API-Key: $API_KEY")
+AMQP_STATUS=$(echo $AMQP_RESULT | jq -r '.data.status')
+
+if [ "$AMQP_STATUS" = "completed" ]; then
+  echo "✅ AMQP Timer Executed Successfully"
+  echo "   Status: $AMQP_STATUS"
+  echo "   Read with: docker exec timer-rabbitmq rabbitmqadmin get queue=timers-test-execution"
+else
+  echo "❌ AMQP Timer Execution Failed"
+  echo "   Status: $AMQP_STATUS"
+  echo "   Error: $(echo $AMQP_RESULT | jq -r '.data.last_error')"
+fi
+echo ""
+
+# Check Postgres timer execution (due at the same time as the NATS timer)
+echo "=== Test 11: Verify Postgres Timer Execution ==="
+PG_RESULT=$(curl -s -X GET $BASE_URL/timers/$PG_TIMER_ID -H "X-API-Key: $API_KEY")
+PG_STATUS=$(echo $PG_RESULT | jq -r '.data.status')
+
+if [ "$PG_STATUS" = "completed" ]; then
+  echo "✅ Postgres Timer Executed Successfully"
+  echo "   Status: $PG_STATUS"
+  echo "   Outbox rows: $(docker exec timer-postgres psql -tA -U timer -d timerdb -c "SELECT COUNT(*) FROM timer_outbox WHERE timer_id = '$PG_TIMER_ID'")"
+else
+  echo "❌ Postgres Timer Execution Failed"
+  echo "   Status: $PG_STATUS"
+  echo "   Error: $(echo $PG_RESULT | jq -r '.data.last_error')"
+fi
+echo ""
+
 # Summary
 echo "=== Test Summary ==="
 echo "HTTP Timer ID: $HTTP_TIMER_ID - Status: $HTTP_STATUS"
 echo "NATS Timer ID: $NATS_TIMER_ID - Status: $NATS_STATUS"
+echo "Kafka Timer ID: $KAFKA_TIMER_ID - Status: $KAFKA_STATUS"
+echo "Redis Timer ID: $REDIS_TIMER_ID - Status: $REDIS_STATUS"
+echo "AMQP Timer ID: $AMQP_TIMER_ID - Status: $AMQP_STATUS"
+echo "Postgres Timer ID: $PG_TIMER_ID - Status: $PG_STATUS"
 echo ""
 
 # Check scheduler logs